@@ -0,0 +1,352 @@
+use minicbor::{Decode, Encode};
+
+use crate::consts;
+use crate::damage::{Armor, DamageType};
+use crate::errors::{Result, SimulationError};
+use crate::fixed::Fixed;
+use crate::physics::DRAG_ONE;
+use crate::trig::Angle;
+use crate::world::{Position, Zone};
+
+/// Tunable parameters of a match. Defaults mirror the values in `consts`.
+///
+/// The config is part of `GameState`, so it is serialized and covered by the
+/// state hash: two players can only agree on a state if they agree on the rules.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Encode, Decode)]
+#[cbor(map)]
+pub struct GameConfig {
+    #[n(0)]
+    pub world_max_x: u32,
+    #[n(1)]
+    pub world_max_y: u32,
+    #[n(2)]
+    pub ticks_per_input: u32,
+    #[n(3)]
+    pub tick_input_api_chunk_size: u32,
+    /// Speed a move input adds to a raft of `standard_raft_mass`
+    #[n(4)]
+    pub velocity_gain_normal: i32,
+    /// Speed a charge adds towards the closest enemy, it may go past `raft_max_speed`
+    #[n(5)]
+    pub velocity_gain_boost: i32,
+    #[n(6)]
+    pub raft_health: u32,
+    #[n(7)]
+    pub raft_width: u32,
+    #[n(8)]
+    pub raft_height: u32,
+    #[n(9)]
+    pub raft_fighter_width: u32,
+    #[n(10)]
+    pub raft_fighter_height: u32,
+    #[n(11)]
+    pub projectile_radius: u32,
+    #[n(12)]
+    pub left_raft_init_pos: Position,
+    #[n(13)]
+    pub right_raft_init_pos: Position,
+    #[n(14)]
+    pub left_raft_max_x: u32,
+    #[n(15)]
+    pub right_raft_min_x: u32,
+    /// Seed of the match's `Rng`, both players have to agree on it up front
+    #[n(16)]
+    pub rng_seed: u64,
+    /// Vertical speed ballistic projectiles lose every tick
+    #[n(17)]
+    pub gravity: i32,
+    /// Strongest wind the seed can draw along each axis
+    #[n(18)]
+    pub max_wind: i32,
+    #[n(19)]
+    pub tide_amplitude: u32,
+    #[n(20)]
+    pub tide_period: u32,
+    /// Fastest a raft can go by paddling alone
+    #[n(21)]
+    pub raft_max_speed: i32,
+    /// Share of its speed a raft loses every tick, out of `physics::DRAG_ONE`
+    #[n(22)]
+    pub raft_drag: u32,
+    /// Mass given to new rafts
+    #[n(23)]
+    pub raft_mass: u32,
+    /// Damage per unit of closing speed when rafts of different teams collide
+    #[n(24)]
+    pub ram_damage: u32,
+    /// Damage multiplier of a raft that is ramming
+    #[n(25)]
+    pub ram_boost_multiplier: u32,
+    /// Ticks a ram lasts, a raft can't ram again before its current ram is over
+    #[n(26)]
+    pub ram_duration: u32,
+    /// Steps of a `trig::Angle` a single aim input turns the selected fighter by
+    #[n(27)]
+    pub aim_step: u32,
+    /// Side of a cell in the broadphase grid projectiles are checked against
+    #[n(28)]
+    pub grid_cell_size: u32,
+    /// Mass that gains the full speed of an input and deals the base ram damage
+    #[n(29)]
+    pub standard_raft_mass: u32,
+    /// Armor given to new rafts
+    #[n(30)]
+    pub raft_armor: Armor,
+    /// Armor given to new fighters
+    #[n(31)]
+    pub fighter_armor: Armor,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            world_max_x: consts::WORLD_MAX_X,
+            world_max_y: consts::WORLD_MAX_Y,
+            ticks_per_input: consts::TICKS_PER_INPUT,
+            tick_input_api_chunk_size: consts::TICK_INPUT_API_CHUNK_SIZE,
+            velocity_gain_normal: consts::VELOCITY_GAIN_NORMAL,
+            velocity_gain_boost: consts::VELOCITY_GAIN_BOOST,
+            raft_health: consts::DEFAULT_RAFT_HEALTH,
+            raft_width: consts::DEFAULT_RAFT_WIDTH,
+            raft_height: consts::DEFAULT_RAFT_HEIGHT,
+            raft_fighter_width: consts::DEFAULT_RAFT_FIGHTER_WIDTH,
+            raft_fighter_height: consts::DEFAULT_RAFT_FIGHTER_HEIGHT,
+            projectile_radius: consts::DEFAULT_PROJECTILE_RADIUS,
+            left_raft_init_pos: consts::LEFT_RAFT_INIT_POS,
+            right_raft_init_pos: consts::RIGHT_RAFT_INIT_POS,
+            left_raft_max_x: consts::LEFT_RAFT_MAX_X,
+            right_raft_min_x: consts::RIGHT_RAFT_MIN_X,
+            rng_seed: consts::DEFAULT_RNG_SEED,
+            gravity: consts::GRAVITY,
+            max_wind: consts::MAX_WIND,
+            tide_amplitude: consts::TIDE_AMPLITUDE,
+            tide_period: consts::TIDE_PERIOD,
+            raft_max_speed: consts::RAFT_MAX_SPEED,
+            raft_drag: consts::RAFT_DRAG,
+            raft_mass: consts::STANDARD_RAFT_MASS,
+            ram_damage: consts::RAM_DAMAGE,
+            ram_boost_multiplier: consts::RAM_BOOST_MULTIPLIER,
+            ram_duration: consts::RAM_DURATION,
+            aim_step: consts::AIM_STEP,
+            grid_cell_size: consts::GRID_CELL_SIZE,
+            standard_raft_mass: consts::STANDARD_RAFT_MASS,
+            raft_armor: consts::RAFT_ARMOR,
+            fighter_armor: consts::FIGHTER_ARMOR,
+        }
+    }
+}
+
+impl GameConfig {
+    /// # Errors
+    ///
+    /// Will return `Err` naming the first field that is inconsistent with the rest of the config
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |field| Err(SimulationError::InvalidConfig { field });
+
+        if self.world_max_x == 0 || i32::try_from(self.world_max_x).is_err() {
+            return invalid("world_max_x");
+        }
+        if self.world_max_y == 0 || i32::try_from(self.world_max_y).is_err() {
+            return invalid("world_max_y");
+        }
+        if self.ticks_per_input == 0 {
+            return invalid("ticks_per_input");
+        }
+        if self.tick_input_api_chunk_size == 0 {
+            return invalid("tick_input_api_chunk_size");
+        }
+        if self.velocity_gain_normal < 0 {
+            return invalid("velocity_gain_normal");
+        }
+        if self.velocity_gain_boost < self.velocity_gain_normal {
+            return invalid("velocity_gain_boost");
+        }
+        if self.raft_max_speed < self.velocity_gain_normal {
+            return invalid("raft_max_speed");
+        }
+        if self.raft_drag > DRAG_ONE {
+            return invalid("raft_drag");
+        }
+        if self.raft_mass == 0 {
+            return invalid("raft_mass");
+        }
+        if self.standard_raft_mass == 0 {
+            return invalid("standard_raft_mass");
+        }
+        if !armor_is_valid(&self.raft_armor) {
+            return invalid("raft_armor");
+        }
+        if !armor_is_valid(&self.fighter_armor) {
+            return invalid("fighter_armor");
+        }
+        if self.ram_boost_multiplier == 0 {
+            return invalid("ram_boost_multiplier");
+        }
+        if self.gravity < 0 {
+            return invalid("gravity");
+        }
+        if self.max_wind < 0 {
+            return invalid("max_wind");
+        }
+        if self.tide_amplitude >= self.world_max_y {
+            return invalid("tide_amplitude");
+        }
+        if self.tide_period == 0 {
+            return invalid("tide_period");
+        }
+        if self.aim_step == 0 || self.aim_step > u32::from(Angle::QUARTER_TURN.steps()) {
+            return invalid("aim_step");
+        }
+        if self.raft_health == 0 {
+            return invalid("raft_health");
+        }
+        if self.raft_width == 0 || self.raft_width >= self.world_max_x {
+            return invalid("raft_width");
+        }
+        if self.raft_height == 0 || self.raft_height >= self.world_max_y {
+            return invalid("raft_height");
+        }
+        if self.raft_fighter_width == 0 || self.raft_fighter_width > self.raft_width {
+            return invalid("raft_fighter_width");
+        }
+        if self.raft_fighter_height == 0 || self.raft_fighter_height > self.raft_height {
+            return invalid("raft_fighter_height");
+        }
+        let diameter = self.projectile_radius.checked_mul(2);
+        if self.projectile_radius == 0 || diameter.is_none_or(|d| d >= self.world_max_y) {
+            return invalid("projectile_radius");
+        }
+        if self.grid_cell_size == 0 {
+            return invalid("grid_cell_size");
+        }
+        if self.left_raft_max_x.saturating_add(self.raft_width) > self.right_raft_min_x {
+            return invalid("left_raft_max_x");
+        }
+        if !self.raft_fits(&self.left_raft_init_pos)
+            || self.left_raft_init_pos.x > Fixed::from_uint(self.left_raft_max_x)
+        {
+            return invalid("left_raft_init_pos");
+        }
+        if !self.raft_fits(&self.right_raft_init_pos)
+            || self.right_raft_init_pos.x < Fixed::from_uint(self.right_raft_min_x)
+        {
+            return invalid("right_raft_init_pos");
+        }
+        Ok(())
+    }
+
+    /// Part of the world rafts of `team` may paddle in.
+    ///
+    /// Team 0 holds the left side up to `left_raft_max_x` and team 1 the right side from
+    /// `right_raft_min_x`, which keeps a duel two-sided. Any further team roams the whole world.
+    #[must_use]
+    pub const fn zone_for_team(&self, team: u32) -> Zone {
+        match team {
+            0 => Zone {
+                min_x: 0,
+                max_x: self.left_raft_max_x,
+            },
+            1 => Zone {
+                min_x: self.right_raft_min_x,
+                max_x: self.world_max_x.saturating_sub(self.raft_width),
+            },
+            _ => Zone {
+                min_x: 0,
+                max_x: self.world_max_x.saturating_sub(self.raft_width),
+            },
+        }
+    }
+
+    /// Number of input windows the tick API expects for `ticks_to_process` ticks.
+    #[must_use]
+    pub const fn input_windows_needed(&self, ticks_to_process: u32) -> u32 {
+        ticks_to_process.div_ceil(self.ticks_per_input)
+    }
+
+    /// Number of u32 input slots the legacy fixed chunk tick API expects for `ticks_to_process` ticks.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the count doesn't fit in a u32
+    pub const fn tick_inputs_needed(&self, ticks_to_process: u32) -> Result<u32> {
+        let windows = self.input_windows_needed(ticks_to_process);
+        match windows.checked_mul(self.tick_input_api_chunk_size) {
+            Some(inputs) => Ok(inputs),
+            None => Err(SimulationError::InputCountOverflow {
+                ticks: ticks_to_process,
+                chunk_size: self.tick_input_api_chunk_size,
+            }),
+        }
+    }
+
+    /// Whether a raft at `pos` lies within the world, judged on whole units like its hitbox.
+    const fn raft_fits(&self, pos: &Position) -> bool {
+        let (x, y) = (pos.x.to_u32(), pos.y.to_u32());
+        x > 0
+            && y > 0
+            && x.saturating_add(self.raft_width) <= self.world_max_x
+            && y.saturating_add(self.raft_height) <= self.world_max_y
+    }
+}
+
+/// Resistances range from -100, double damage, to 100, immune.
+fn armor_is_valid(armor: &Armor) -> bool {
+    [DamageType::Kinetic, DamageType::Explosive, DamageType::Fire, DamageType::Impact]
+        .into_iter()
+        .all(|damage_type| (-100..=100).contains(&armor.resistances.against(damage_type)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Mutation = fn(&mut GameConfig);
+
+    #[test]
+    fn it_accepts_the_default_config() {
+        assert!(GameConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn it_rejects_fixed_chunk_input_counts_past_u32() {
+        let config = GameConfig {
+            tick_input_api_chunk_size: u32::MAX / 2,
+            ..GameConfig::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.tick_inputs_needed(config.ticks_per_input).unwrap(), u32::MAX / 2);
+        assert!(matches!(
+            config.tick_inputs_needed(2 * config.ticks_per_input + 1),
+            Err(SimulationError::InputCountOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn it_rejects_inconsistent_configs() {
+        let cases: [(&str, Mutation); 13] = [
+            ("ticks_per_input", |c| c.ticks_per_input = 0),
+            ("gravity", |c| c.gravity = -1),
+            ("tide_period", |c| c.tide_period = 0),
+            ("raft_max_speed", |c| c.raft_max_speed = c.velocity_gain_normal - 1),
+            ("raft_drag", |c| c.raft_drag = DRAG_ONE + 1),
+            ("raft_width", |c| c.raft_width = c.world_max_x),
+            ("raft_fighter_height", |c| c.raft_fighter_height = c.raft_height + 1),
+            ("left_raft_max_x", |c| c.right_raft_min_x = c.left_raft_max_x),
+            ("right_raft_init_pos", |c| c.right_raft_init_pos.x = Fixed::from_uint(c.world_max_x)),
+            ("projectile_radius", |c| c.projectile_radius = 0x8000_0000),
+            ("grid_cell_size", |c| c.grid_cell_size = 0),
+            ("standard_raft_mass", |c| c.standard_raft_mass = 0),
+            ("raft_armor", |c| c.raft_armor.resistances.fire = i32::MIN),
+        ];
+
+        for (expected, mutate) in cases {
+            let mut config = GameConfig::default();
+            mutate(&mut config);
+            match config.validate() {
+                Err(SimulationError::InvalidConfig { field }) => assert_eq!(field, expected),
+                other => panic!("expected invalid {expected}, got {other:?}"),
+            }
+        }
+    }
+}
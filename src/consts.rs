@@ -1,14 +1,46 @@
+use crate::damage::{Armor, Resistances};
+use crate::fixed::Fixed;
 use crate::world::{Position, Velocity};
 
 pub const WORLD_MAX_X: u32 = 10_000;
 pub const WORLD_MAX_Y: u32 = 10_000;
 pub const TICKS_PER_INPUT: u32 = 5;
 pub const TICK_INPUT_API_CHUNK_SIZE: u32 = 10;
-pub const VELOCITY_GAIN_NORMAL: i32 = 5;
+pub const VELOCITY_GAIN_NORMAL: i32 = 8;
 pub const VELOCITY_GAIN_BOOST: i32 = 50;
+pub const GRAVITY: i32 = 1;
+pub const RAFT_MAX_SPEED: i32 = 20;
+/// Out of `physics::DRAG_ONE`, the share of its speed a raft loses every tick
+pub const RAFT_DRAG: u32 = 64;
+/// Mass of a regular raft, a raft twice as heavy gains half the speed from each input
+pub const STANDARD_RAFT_MASS: u32 = 100;
+/// Damage per unit of closing speed when two rafts collide
+pub const RAM_DAMAGE: u32 = 5;
+pub const RAM_BOOST_MULTIPLIER: u32 = 3;
+pub const RAM_DURATION: u32 = 40;
+/// A 64th of a turn, a little under 6 degrees
+pub const AIM_STEP: u32 = 16;
 
 pub const DEFAULT_RAFT_HEALTH: u32 = 10_000;
 
+/// Wooden rafts shrug off some bullets but burn easily
+pub const RAFT_ARMOR: Armor = Armor {
+    rating: 2,
+    resistances: Resistances {
+        kinetic: 25,
+        explosive: 0,
+        fire: -25,
+        impact: 0,
+    },
+};
+pub const FIGHTER_ARMOR: Armor = Armor::NONE;
+
+pub const DEFAULT_RNG_SEED: u64 = 0;
+
+pub const MAX_WIND: i32 = 2;
+pub const TIDE_AMPLITUDE: u32 = 100;
+pub const TIDE_PERIOD: u32 = 2000;
+
 pub const DEFAULT_RAFT_WIDTH: u32 = WORLD_MAX_X / 4;
 pub const DEFAULT_RAFT_HEIGHT: u32 = WORLD_MAX_Y / 10;
 
@@ -18,15 +50,21 @@ pub const DEFAULT_RAFT_FIGHTER_HEIGHT: u32 = DEFAULT_RAFT_HEIGHT / 10;
 const DEFAULT_PROJECTILE_DIAMETER: u32 = WORLD_MAX_X / 50;
 pub const DEFAULT_PROJECTILE_RADIUS: u32 = DEFAULT_PROJECTILE_DIAMETER / 2;
 
+/// Side of a cell in the collision broadphase grid, two fighters wide
+pub const GRID_CELL_SIZE: u32 = 2 * DEFAULT_RAFT_FIGHTER_WIDTH;
+
 pub const LEFT_RAFT_INIT_POS: Position = Position {
-    x: WORLD_MAX_X / 10,
-    y: WORLD_MAX_Y / 4,
+    x: Fixed::from_uint(WORLD_MAX_X / 10),
+    y: Fixed::from_uint(WORLD_MAX_Y / 4),
 };
 pub const RIGHT_RAFT_INIT_POS: Position = Position {
-    x: WORLD_MAX_X * 9 / 10 - DEFAULT_RAFT_WIDTH,
-    y: WORLD_MAX_Y / 4,
+    x: Fixed::from_uint(WORLD_MAX_X - DEFAULT_RAFT_WIDTH),
+    y: Fixed::from_uint(WORLD_MAX_Y / 4),
+};
+pub const NO_VELOCITY: Velocity = Velocity {
+    vx: Fixed::ZERO,
+    vy: Fixed::ZERO,
 };
-pub const NO_VELOCITY: Velocity = Velocity { vx: 0, vy: 0 };
 
 pub const LEFT_RAFT_MAX_X: u32 = WORLD_MAX_X / 2 - DEFAULT_RAFT_WIDTH;
 pub const RIGHT_RAFT_MIN_X: u32 = WORLD_MAX_X / 2;
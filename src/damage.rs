@@ -0,0 +1,264 @@
+use minicbor::{Decode, Encode};
+
+use crate::rng::Rng;
+use crate::world::{Raft, RaftFighter};
+
+/// What a hit is made of, targets resist each type separately.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageType {
+    Kinetic,
+    Explosive,
+    Fire,
+    /// Rafts running into each other
+    Impact,
+}
+
+/// Percent of each damage type a target ignores, negative values are weaknesses.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cbor(map)]
+pub struct Resistances {
+    #[n(0)]
+    pub kinetic: i32,
+    #[n(1)]
+    pub explosive: i32,
+    #[n(2)]
+    pub fire: i32,
+    #[n(3)]
+    pub impact: i32,
+}
+
+impl Resistances {
+    pub const NONE: Self = Self {
+        kinetic: 0,
+        explosive: 0,
+        fire: 0,
+        impact: 0,
+    };
+
+    #[must_use]
+    pub const fn against(&self, damage_type: DamageType) -> i32 {
+        match damage_type {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Explosive => self.explosive,
+            DamageType::Fire => self.fire,
+            DamageType::Impact => self.impact,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cbor(map)]
+pub struct Armor {
+    /// Flat damage soaked by every hit once resistances are applied
+    #[n(0)]
+    pub rating: u32,
+    #[n(1)]
+    pub resistances: Resistances,
+}
+
+impl Armor {
+    pub const NONE: Self = Self {
+        rating: 0,
+        resistances: Resistances::NONE,
+    };
+}
+
+/// A single hit before the target's armor is taken into account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    pub damage: u32,
+    pub damage_type: DamageType,
+    /// Percent chance the hit is critical, 0 never rolls the `Rng`
+    pub crit_chance: u32,
+    /// Damage of a critical hit in percent of the regular damage
+    pub crit_multiplier: u32,
+}
+
+impl Hit {
+    /// Hit that can never be critical.
+    #[must_use]
+    pub const fn new(damage: u32, damage_type: DamageType) -> Self {
+        Self {
+            damage,
+            damage_type,
+            crit_chance: 0,
+            crit_multiplier: 100,
+        }
+    }
+
+    /// Damage the hit deals to a target wearing `armor`.
+    ///
+    /// Rolls for a crit first, then applies the resistance against the hit's type and
+    /// finally the flat armor rating. The target's remaining health plays no part.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn resolve(&self, armor: &Armor, rng: &mut Rng) -> u32 {
+        let mut damage = u64::from(self.damage);
+        if self.crit_chance > 0 && rng.chance(self.crit_chance, 100) {
+            damage = damage.saturating_mul(u64::from(self.crit_multiplier)) / 100;
+        }
+
+        // held to the range `GameConfig::validate` allows, armor decoded with an entity
+        // never went through it
+        let resistance = armor.resistances.against(self.damage_type).clamp(-100, 100);
+        // resistance <= 100 so the factor is never negative
+        let factor = (100 - i64::from(resistance)) as u64;
+        let resisted = damage.saturating_mul(factor) / 100;
+
+        resisted
+            .saturating_sub(u64::from(armor.rating))
+            .min(u64::from(u32::MAX)) as u32
+    }
+}
+
+/// Anything that has health and can be sunk or knocked out by hits.
+pub trait Damageable {
+    fn armor(&self) -> &Armor;
+
+    fn health(&self) -> u32;
+
+    /// Sets the health, a target left with none is deactivated.
+    fn set_health(&mut self, health: u32);
+
+    /// Applies `hit` to the target and returns the damage it actually took.
+    fn take_damage(&mut self, hit: &Hit, rng: &mut Rng) -> u32 {
+        let health = self.health();
+        if health == 0 {
+            return 0;
+        }
+        let damage = hit.resolve(self.armor(), rng).min(health);
+        self.set_health(health - damage);
+        damage
+    }
+}
+
+impl Damageable for Raft {
+    fn armor(&self) -> &Armor {
+        &self.armor
+    }
+
+    fn health(&self) -> u32 {
+        self.curr_health
+    }
+
+    fn set_health(&mut self, health: u32) {
+        self.curr_health = health;
+        if health == 0 {
+            self.entity.is_active = false;
+        }
+    }
+}
+
+impl Damageable for RaftFighter {
+    fn armor(&self) -> &Armor {
+        &self.armor
+    }
+
+    fn health(&self) -> u32 {
+        self.curr_health
+    }
+
+    fn set_health(&mut self, health: u32) {
+        self.curr_health = health;
+        if health == 0 {
+            self.entity.is_active = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::paddle::PlayerId;
+    use crate::world::GameState;
+
+    const PLATED: Armor = Armor {
+        rating: 5,
+        resistances: Resistances {
+            kinetic: 50,
+            explosive: 0,
+            fire: -50,
+            impact: 100,
+        },
+    };
+
+    #[test]
+    fn it_applies_resistances_then_armor_rating() {
+        let mut rng = Rng::new(0);
+        let resolve = |damage_type| Hit::new(100, damage_type).resolve(&PLATED, &mut Rng::new(0));
+
+        assert_eq!(resolve(DamageType::Kinetic), 45);
+        assert_eq!(resolve(DamageType::Explosive), 95);
+        assert_eq!(resolve(DamageType::Fire), 145);
+        assert_eq!(resolve(DamageType::Impact), 0);
+        assert_eq!(Hit::new(3, DamageType::Explosive).resolve(&PLATED, &mut rng), 0);
+        assert_eq!(Hit::new(100, DamageType::Kinetic).resolve(&Armor::NONE, &mut rng), 100);
+    }
+
+    #[test]
+    fn it_saturates_extreme_hits_and_resistances() {
+        let brittle = Armor {
+            rating: 0,
+            resistances: Resistances {
+                kinetic: i32::MIN,
+                explosive: i32::MAX,
+                ..Resistances::NONE
+            },
+        };
+        let hit = Hit {
+            crit_chance: 100,
+            crit_multiplier: u32::MAX,
+            ..Hit::new(u32::MAX, DamageType::Kinetic)
+        };
+        let mut rng = Rng::new(0);
+        assert_eq!(hit.resolve(&brittle, &mut rng), u32::MAX);
+        // a weakness never more than doubles the damage
+        assert_eq!(Hit::new(100, DamageType::Kinetic).resolve(&brittle, &mut rng), 200);
+        assert_eq!(Hit::new(100, DamageType::Explosive).resolve(&brittle, &mut rng), 0);
+    }
+
+    #[test]
+    fn it_rolls_crits_from_the_rng() {
+        let hit = Hit {
+            crit_chance: 50,
+            crit_multiplier: 200,
+            ..Hit::new(100, DamageType::Kinetic)
+        };
+        let roll = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..64).map(|_| hit.resolve(&Armor::NONE, &mut rng)).collect::<Vec<_>>()
+        };
+
+        let rolls = roll(7);
+        assert_eq!(rolls, roll(7));
+        assert!(rolls.iter().all(|&d| d == 100 || d == 200));
+        assert!(rolls.contains(&100) && rolls.contains(&200));
+
+        // hits that can't crit leave the rng untouched
+        let mut rng = Rng::new(7);
+        Hit::new(100, DamageType::Kinetic).resolve(&Armor::NONE, &mut rng);
+        assert_eq!(rng, Rng::new(7));
+    }
+
+    #[test]
+    fn it_deals_the_same_damage_whatever_health_is_left() {
+        let mut rng = Rng::new(0);
+        let mut raft = GameState::new().rafts[PlayerId(0).raft()].clone();
+        let hit = Hit::new(100, DamageType::Explosive);
+        let expected = hit.resolve(&raft.armor, &mut rng);
+
+        assert_eq!(raft.take_damage(&hit, &mut rng), expected);
+        raft.curr_health = raft.max_health / 10;
+        assert_eq!(raft.take_damage(&hit, &mut rng), expected);
+
+        // the last hit only takes what is left and sinks the raft
+        raft.curr_health = 1;
+        assert_eq!(raft.take_damage(&hit, &mut rng), 1);
+        assert!(!raft.is_afloat());
+        assert_eq!(raft.take_damage(&hit, &mut rng), 0);
+    }
+}
@@ -0,0 +1,104 @@
+use minicbor::{Decode, Encode};
+
+use crate::config::GameConfig;
+use crate::fixed::Fixed;
+use crate::rng::Rng;
+use crate::world::Velocity;
+
+/// Forces the sea and the sky apply to everything on the water.
+///
+/// The wind is drawn from the match's `Rng` when the match starts, so the seed in
+/// `GameConfig` decides the weather. Like the rest of `GameState` it is serialized
+/// and covered by the state hash.
+#[derive(Clone, Encode, Decode)]
+#[cbor(map)]
+pub struct Environment {
+    /// Drift added to every projectile's position each tick
+    #[n(0)]
+    pub wind: Velocity,
+    /// How far the tide lifts rafts above low water
+    #[n(1)]
+    pub tide_amplitude: u32,
+    /// Ticks from one high tide to the next
+    #[n(2)]
+    pub tide_period: u32,
+}
+
+impl Environment {
+    pub fn new(config: &GameConfig, rng: &mut Rng) -> Self {
+        let max_wind = config.max_wind;
+        Self {
+            wind: Velocity {
+                vx: Fixed::from_int(rng.range_i32(-max_wind, max_wind)),
+                vy: Fixed::from_int(rng.range_i32(-max_wind, max_wind)),
+            },
+            tide_amplitude: config.tide_amplitude,
+            tide_period: config.tide_period,
+        }
+    }
+
+    /// Height of the tide above low water at `tick`.
+    ///
+    /// The tide follows a triangle wave: high at tick 0, low half a period later.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_lossless)]
+    pub const fn tide_level(&self, tick: u32) -> u32 {
+        if self.tide_period == 0 {
+            return 0;
+        }
+        let phase = tick % self.tide_period;
+        let distance_from_low = self.tide_period.abs_diff(2 * phase);
+        // fits in u32 since distance_from_low <= tide_period
+        (self.tide_amplitude as u64 * distance_from_low as u64 / self.tide_period as u64) as u32
+    }
+
+    /// Vertical distance the tide moves rafts during `tick`, taking them from the level at
+    /// `tick` to the level at `tick + 1`.
+    ///
+    /// Summing it over every tick gives back the level, so rafts never drift with the tide.
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub const fn tide_delta(&self, tick: u32) -> i32 {
+        self.tide_level(tick.wrapping_add(1)) as i32 - self.tide_level(tick) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_draws_the_wind_from_the_seed() {
+        let config = GameConfig::default();
+        let wind = |seed| {
+            let mut rng = Rng::new(seed);
+            Environment::new(&config, &mut rng).wind
+        };
+
+        for seed in 0..32 {
+            let (a, b) = (wind(seed), wind(seed));
+            assert_eq!((a.vx, a.vy), (b.vx, b.vy));
+            let max_wind = Fixed::from_int(config.max_wind);
+            assert!(a.vx.abs() <= max_wind && a.vy.abs() <= max_wind);
+        }
+        assert!((0..32).map(wind).any(|w| w.vx != Fixed::ZERO || w.vy != Fixed::ZERO));
+    }
+
+    #[test]
+    fn it_cycles_the_tide_without_drift() {
+        let config = GameConfig::default();
+        let environment = Environment::new(&config, &mut Rng::new(0));
+        let period = environment.tide_period;
+
+        assert_eq!(environment.tide_level(0), environment.tide_amplitude);
+        assert_eq!(environment.tide_level(period / 2), 0);
+        assert_eq!(environment.tide_level(period), environment.tide_amplitude);
+
+        let mut height = 0;
+        for tick in 0..period {
+            height += environment.tide_delta(tick);
+            assert!(height <= 0 && height.unsigned_abs() <= environment.tide_amplitude);
+        }
+        assert_eq!(height, 0);
+    }
+}
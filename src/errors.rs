@@ -1,5 +1,11 @@
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use core::convert::Infallible;
 use core::fmt;
 
+use crate::world::MatchOutcome;
+
 pub type Result<T> = core::result::Result<T, SimulationError>;
 
 #[derive(Debug)]
@@ -17,6 +23,62 @@ pub enum SimulationError {
         end_tick: u32,
     },
     USizeToU32Conversion {},
+    MatchFinished {
+        num_tick: u32,
+        outcome: MatchOutcome,
+    },
+    InvalidConfig {
+        field: &'static str,
+    },
+    MissingInputWindow {
+        window: u32,
+        expected: u32,
+    },
+    InputWindowTooLong {
+        window: u32,
+        received: u32,
+        max: u32,
+    },
+    TruncatedInputWindow {
+        window: u32,
+        declared: u32,
+        available: u32,
+    },
+    TrailingInput {
+        received: u32,
+    },
+    /// The legacy fixed chunk API can't take inputs for that many ticks
+    InputCountOverflow {
+        ticks: u32,
+        chunk_size: u32,
+    },
+    InvalidPlayerCount {
+        received: u32,
+        expected: u32,
+    },
+    NotEnoughTeams {},
+    UnknownPlayer {
+        received: u32,
+        players: u32,
+    },
+    PredictionTooFar {
+        window: u32,
+        confirmed: u32,
+    },
+    StateDecode {
+        /// Byte offset into the encoding where decoding failed, if minicbor knows it
+        position: Option<usize>,
+        message: String,
+    },
+    StateEncode {
+        message: String,
+    },
+    /// States from before the versioned envelope are reported as version 0
+    UnsupportedStateVersion {
+        received: u32,
+        oldest: u32,
+        latest: u32,
+    },
 }
 
 impl fmt::Display for SimulationError {
@@ -44,6 +106,96 @@ impl fmt::Display for SimulationError {
             Self::USizeToU32Conversion {} => {
                 write!(f, "Failed to convert USizeToU32")
             }
+            Self::MatchFinished { num_tick, outcome } => {
+                write!(f, "Match already finished at tick {}: {:?}", num_tick, outcome)
+            }
+            Self::InvalidConfig { field } => {
+                write!(f, "Invalid game config: {}", field)
+            }
+            Self::MissingInputWindow { window, expected } => {
+                write!(f, "Input stream ended at window {} of {}", window, expected)
+            }
+            Self::InputWindowTooLong {
+                window,
+                received,
+                max,
+            } => {
+                write!(
+                    f,
+                    "Input window {} too long: received {} max {}",
+                    window, received, max
+                )
+            }
+            Self::TruncatedInputWindow {
+                window,
+                declared,
+                available,
+            } => {
+                write!(
+                    f,
+                    "Input window {} truncated: declared {} available {}",
+                    window, declared, available
+                )
+            }
+            Self::TrailingInput { received } => {
+                write!(f, "Trailing input after last window: {} values", received)
+            }
+            Self::InputCountOverflow { ticks, chunk_size } => {
+                write!(f, "Input for {} ticks in chunks of {} overflows u32", ticks, chunk_size)
+            }
+            Self::InvalidPlayerCount { received, expected } => {
+                write!(
+                    f,
+                    "Invalid number of player input streams: received {} expected {}",
+                    received, expected
+                )
+            }
+            Self::NotEnoughTeams {} => {
+                write!(f, "A match needs rafts afloat from at least two teams")
+            }
+            Self::UnknownPlayer { received, players } => {
+                write!(f, "Unknown player {}: the match has {} players", received, players)
+            }
+            Self::PredictionTooFar { window, confirmed } => {
+                write!(
+                    f,
+                    "Can't predict window {}: inputs are only confirmed up to window {}",
+                    window, confirmed
+                )
+            }
+            Self::StateDecode { position: Some(position), message } => {
+                write!(f, "Failed to decode game state at byte {}: {}", position, message)
+            }
+            Self::StateDecode { position: None, message } => {
+                write!(f, "Failed to decode game state: {}", message)
+            }
+            Self::StateEncode { message } => {
+                write!(f, "Failed to encode game state: {}", message)
+            }
+            Self::UnsupportedStateVersion { received, oldest, latest } => {
+                write!(
+                    f,
+                    "Unsupported game state version {}: versions {} to {} can be decoded",
+                    received, oldest, latest
+                )
+            }
+        }
+    }
+}
+
+impl From<minicbor::decode::Error> for SimulationError {
+    fn from(error: minicbor::decode::Error) -> Self {
+        Self::StateDecode {
+            position: error.position(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<minicbor::encode::Error<Infallible>> for SimulationError {
+    fn from(error: minicbor::encode::Error<Infallible>) -> Self {
+        Self::StateEncode {
+            message: error.to_string(),
         }
     }
 }
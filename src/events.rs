@@ -0,0 +1,39 @@
+use crate::world::{FighterId, GunTypes, RaftId};
+
+/// Something that happened during a tick call, collected in `GameState::events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    Hit(HitEvent),
+}
+
+/// What a projectile hit.
+///
+/// Fighters order before the raft they stand on since they shield its hull.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HitTarget {
+    Fighter { raft: RaftId, fighter: FighterId },
+    Raft { raft: RaftId },
+}
+
+impl HitTarget {
+    /// Raft that was hit or carries the fighter that was hit.
+    #[must_use]
+    pub const fn raft(&self) -> RaftId {
+        match self {
+            Self::Fighter { raft, .. } | Self::Raft { raft } => *raft,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HitEvent {
+    pub tick: u32,
+    /// Team of the raft that fired the projectile
+    pub team: u32,
+    pub gun: GunTypes,
+    pub target: HitTarget,
+    /// Damage the target actually took once its armor is accounted for
+    pub damage: u32,
+    /// Caught in the blast of a hit on another target rather than hit directly
+    pub splash: bool,
+}
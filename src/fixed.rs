@@ -0,0 +1,244 @@
+use core::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use minicbor::{Decode, Encode};
+
+/// Signed fixed point number with `FRAC_BITS` fractional bits, stored in an `i64`.
+///
+/// Positions and velocities use it so things can move by a fraction of a unit per tick
+/// without floats. Every operation is plain integer arithmetic, so native, wasm and Stylus
+/// builds agree on every result. The operators saturate at `MIN` and `MAX` in debug and
+/// release builds alike; the `checked_` variants report overflow instead.
+///
+/// It is encoded as its raw bits, frontends divide by `ONE` to get units back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
+#[cbor(transparent)]
+pub struct Fixed(#[n(0)] i64);
+
+impl Fixed {
+    pub const FRAC_BITS: u32 = 16;
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << Self::FRAC_BITS);
+    pub const MIN: Self = Self(i64::MIN);
+    pub const MAX: Self = Self(i64::MAX);
+
+    /// Whole number of units, always exact.
+    #[must_use]
+    pub const fn from_int(units: i32) -> Self {
+        Self((units as i64) << Self::FRAC_BITS)
+    }
+
+    /// Whole number of units, always exact.
+    #[must_use]
+    pub const fn from_uint(units: u32) -> Self {
+        Self((units as i64) << Self::FRAC_BITS)
+    }
+
+    #[must_use]
+    pub const fn from_bits(bits: i64) -> Self {
+        Self(bits)
+    }
+
+    #[must_use]
+    pub const fn to_bits(self) -> i64 {
+        self.0
+    }
+
+    /// Whole units, rounded down.
+    #[must_use]
+    pub const fn floor(self) -> i64 {
+        self.0 >> Self::FRAC_BITS
+    }
+
+    /// Whole units rounded down and clamped to `u32`, for hitboxes and the broadphase grid.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub const fn to_u32(self) -> u32 {
+        let units = self.floor();
+        if units < 0 {
+            0
+        } else if units > u32::MAX as i64 {
+            u32::MAX
+        } else {
+            units as u32
+        }
+    }
+
+    /// Value in units for rendering. Floats must never flow back into the simulation.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::ONE.0 as f64
+    }
+
+    #[must_use]
+    pub const fn abs(self) -> Self {
+        Self(self.0.saturating_abs())
+    }
+
+    #[must_use]
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    #[must_use]
+    pub const fn checked_add(self, other: Self) -> Option<Self> {
+        match self.0.checked_add(other.0) {
+            Some(bits) => Some(Self(bits)),
+            None => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn checked_sub(self, other: Self) -> Option<Self> {
+        match self.0.checked_sub(other.0) {
+            Some(bits) => Some(Self(bits)),
+            None => None,
+        }
+    }
+
+    /// Product rounded towards zero, `None` if it doesn't fit.
+    #[must_use]
+    pub const fn checked_mul(self, other: Self) -> Option<Self> {
+        narrow((self.0 as i128 * other.0 as i128) >> Self::FRAC_BITS)
+    }
+
+    /// Quotient rounded towards zero, `None` on division by zero or if it doesn't fit.
+    #[must_use]
+    pub const fn checked_div(self, other: Self) -> Option<Self> {
+        if other.0 == 0 {
+            return None;
+        }
+        narrow(((self.0 as i128) << Self::FRAC_BITS) / other.0 as i128)
+    }
+
+    #[must_use]
+    pub const fn saturating_add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+
+    #[must_use]
+    pub const fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+
+    #[must_use]
+    pub const fn saturating_mul(self, other: Self) -> Self {
+        saturate((self.0 as i128 * other.0 as i128) >> Self::FRAC_BITS)
+    }
+
+    /// Quotient rounded towards zero, dividing by zero saturates away from it.
+    #[must_use]
+    pub const fn saturating_div(self, other: Self) -> Self {
+        if other.0 == 0 {
+            return if self.0 < 0 { Self::MIN } else { Self::MAX };
+        }
+        saturate(((self.0 as i128) << Self::FRAC_BITS) / other.0 as i128)
+    }
+
+    /// Scales the value by `numerator / denominator`, rounded towards zero and saturating.
+    ///
+    /// The product is taken in 128 bits, so scaling by a ratio of integers is exact up to the
+    /// final rounding. A zero `denominator` saturates away from zero like `saturating_div`.
+    #[must_use]
+    pub const fn mul_div(self, numerator: i64, denominator: i64) -> Self {
+        if denominator == 0 {
+            return if (self.0 < 0) ^ (numerator < 0) { Self::MIN } else { Self::MAX };
+        }
+        saturate(self.0 as i128 * numerator as i128 / denominator as i128)
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn narrow(bits: i128) -> Option<Fixed> {
+    if bits < i64::MIN as i128 || bits > i64::MAX as i128 {
+        None
+    } else {
+        Some(Fixed(bits as i64))
+    }
+}
+
+const fn saturate(bits: i128) -> Fixed {
+    match narrow(bits) {
+        Some(fixed) => fixed,
+        None if bits < 0 => Fixed::MIN,
+        None => Fixed::MAX,
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.saturating_add(other)
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.saturating_sub(other)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.saturating_neg())
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_keeps_fractions_of_a_unit() {
+        let half = Fixed::ONE.mul_div(1, 2);
+        assert_eq!(half + half, Fixed::ONE);
+        assert_eq!((half + Fixed::from_int(3)).floor(), 3);
+        assert_eq!((-half).floor(), -1);
+        assert_eq!((-half).to_u32(), 0);
+        assert_eq!(Fixed::from_uint(u32::MAX).to_u32(), u32::MAX);
+        assert_eq!(Fixed::from_int(-7).mul_div(1, 2), Fixed::from_int(-4) + half);
+        assert!((Fixed::from_int(3).to_f64() - 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn it_multiplies_and_divides_exactly() {
+        let (two, three) = (Fixed::from_int(2), Fixed::from_int(3));
+        assert_eq!(two.checked_mul(-three), Some(Fixed::from_int(-6)));
+        assert_eq!(three.checked_div(two), Some(Fixed::ONE + Fixed::ONE.mul_div(1, 2)));
+        // rounded towards zero, a third doesn't add back up to one
+        let third = Fixed::ONE.checked_div(three).unwrap();
+        assert_eq!(third + third + third, Fixed::ONE - Fixed::from_bits(1));
+        assert_eq!((-Fixed::ONE).saturating_div(three), -third);
+    }
+
+    #[test]
+    fn it_saturates_or_reports_overflow() {
+        assert_eq!(Fixed::MAX.checked_add(Fixed::ONE), None);
+        assert_eq!(Fixed::MIN.checked_sub(Fixed::ONE), None);
+        assert_eq!(Fixed::MAX + Fixed::ONE, Fixed::MAX);
+        assert_eq!(Fixed::MIN - Fixed::ONE, Fixed::MIN);
+        assert_eq!(-Fixed::MIN, Fixed::MAX);
+        assert_eq!(Fixed::MAX.checked_mul(Fixed::from_int(2)), None);
+        assert_eq!(Fixed::MAX.saturating_mul(Fixed::from_int(-2)), Fixed::MIN);
+        assert_eq!(Fixed::ONE.checked_div(Fixed::ZERO), None);
+        assert_eq!(Fixed::from_int(-1).saturating_div(Fixed::ZERO), Fixed::MIN);
+        assert_eq!(Fixed::MAX.mul_div(3, 2), Fixed::MAX);
+    }
+}
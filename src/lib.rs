@@ -10,10 +10,30 @@ pub mod web_entry;
 
 pub mod consts;
 
+pub mod config;
+
 pub mod world;
 
 pub mod errors;
 
+pub mod rng;
+
+pub mod environment;
+
+pub mod fixed;
+
+pub mod trig;
+
 pub mod physics;
 
+pub mod damage;
+
+pub mod events;
+
+pub mod weapons;
+
 pub mod paddle;
+
+pub mod rollback;
+
+pub mod schema;
@@ -4,58 +4,86 @@ use alloc::vec::Vec;
 use alloc::string::String;
 use alloc::vec;
 
+use crate::config::GameConfig;
 use crate::consts;
+use crate::damage::{DamageType, Damageable, Hit};
+use crate::environment::Environment;
 
 use crate::errors::Result;
+use crate::events::{GameEvent, HitEvent, HitTarget};
+use crate::fixed::Fixed;
 use crate::errors::SimulationError;
-use crate::physics::Collision;
+use crate::physics::{Collision, SpatialGrid};
+use crate::rng::Rng;
+use crate::trig::Angle;
+use crate::weapons::WeaponSpec;
 use crate::world::Bearings;
 use crate::world::GunTypes;
+use crate::world::MatchOutcome;
 use crate::world::RaftFighter;
 use crate::world::Style;
+use crate::world::{Components, FighterId, GenerationalId, ProjectileId, RaftId};
 use crate::world::{Entity, GameState, Position, Projectile, Raft, Velocity};
 
 #[cfg(test)]
 use strum_macros::{EnumCount, EnumIter};
 
+/// Owner of an input stream, the index of the raft it steers in spawn order.
+/// A player's inputs only ever act on their own raft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerId(pub u32);
+
+impl PlayerId {
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Raft the player steers. Rafts are spawned in player order and never despawned, so
+    /// the player's raft keeps the first generation of the slot matching their index.
+    #[must_use]
+    pub fn raft(self) -> RaftId {
+        RaftId::new(self.0, 0)
+    }
+}
+
+/// Something a player asks their own raft to do.
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq, Eq, EnumIter, EnumCount))]
-pub enum GameInput {
+pub enum Action {
     NoOp,
-    DeprecatedShootLeftRaft,
-    MoveLeftRaftRight,
-    MoveLeftRaftLeft,
-    DeprecatedShootRightRaft,
-    MoveRightRaftRight,
-    MoveRightRaftLeft,
-    DeprecatedAddProjectile,
-    MoveLeftFastRaftLeft,
-    MoveLeftFastRaftRight,
-    MoveUpRaftRight,
-    MoveRightRaftDown,
-    MoveUpRaftLeft,
-    MoveLeftRaftDown,
+    Fire,
+    MoveRight,
+    MoveLeft,
+    MoveUp,
+    MoveDown,
+    Charge,
+    SelectNextFighter,
+    AimClockwise,
+    AimCounterClockwise,
+    /// Charge that may cross the raft's border and hits harder on impact
+    Ram,
+    /// Turns the selected fighter towards the closest raft of another team still afloat
+    AimAtEnemy,
 }
 
-impl GameInput {
+impl Action {
     /// # Errors
     ///
-    /// Will return `Err` if `input` does not exist as a valid game input
+    /// Will return `Err` if `input` does not exist as a valid action
     pub const fn from(input: u32) -> Result<Self> {
         match input {
-            0 => Ok(Self::DeprecatedShootLeftRaft),
-            1 => Ok(Self::MoveLeftRaftRight),
-            2 => Ok(Self::MoveLeftRaftLeft),
-            3 => Ok(Self::DeprecatedShootRightRaft),
-            4 => Ok(Self::MoveRightRaftRight),
-            5 => Ok(Self::MoveRightRaftLeft),
-            6 => Ok(Self::DeprecatedAddProjectile),
-            7 => Ok(Self::MoveLeftFastRaftLeft),
-            8 => Ok(Self::MoveLeftFastRaftRight),
-            9 => Ok(Self::MoveUpRaftRight),
-            10 => Ok(Self::MoveRightRaftDown),
-            11 => Ok(Self::MoveUpRaftLeft),
-            12 => Ok(Self::MoveLeftRaftDown),
+            0 => Ok(Self::Fire),
+            1 => Ok(Self::MoveRight),
+            2 => Ok(Self::MoveLeft),
+            3 => Ok(Self::MoveUp),
+            4 => Ok(Self::MoveDown),
+            5 => Ok(Self::Charge),
+            6 => Ok(Self::SelectNextFighter),
+            7 => Ok(Self::AimClockwise),
+            8 => Ok(Self::AimCounterClockwise),
+            9 => Ok(Self::Ram),
+            10 => Ok(Self::AimAtEnemy),
             86 => Ok(Self::NoOp),
             received => Err(SimulationError::InvalidInput { received }),
         }
@@ -64,139 +92,326 @@ impl GameInput {
     #[must_use]
     pub const fn to_u32(&self) -> u32 {
         match self {
-            Self::DeprecatedShootLeftRaft => 0,
-            Self::MoveLeftRaftRight => 1,
-            Self::MoveLeftRaftLeft => 2,
-            Self::DeprecatedShootRightRaft => 3,
-            Self::MoveRightRaftRight => 4,
-            Self::MoveRightRaftLeft => 5,
-            Self::DeprecatedAddProjectile => 6,
-            Self::MoveLeftFastRaftLeft => 7,
-            Self::MoveLeftFastRaftRight => 8,
-            Self::MoveUpRaftRight => 9,
-            Self::MoveRightRaftDown => 10,
-            Self::MoveUpRaftLeft => 11,
-            Self::MoveLeftRaftDown => 12,
+            Self::Fire => 0,
+            Self::MoveRight => 1,
+            Self::MoveLeft => 2,
+            Self::MoveUp => 3,
+            Self::MoveDown => 4,
+            Self::Charge => 5,
+            Self::SelectNextFighter => 6,
+            Self::AimClockwise => 7,
+            Self::AimCounterClockwise => 8,
+            Self::Ram => 9,
+            Self::AimAtEnemy => 10,
             Self::NoOp => 86,
         }
     }
 }
 
+/// Layout of each player's flat `u32` input stream passed to the tick API.
+///
+/// Either way the stream holds one window of actions per `ticks_per_input` ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// Legacy layout: every window is exactly `tick_input_api_chunk_size` slots,
+    /// padded with `Action::NoOp`
+    FixedChunks,
+    /// Every window starts with the number of inputs that follow it,
+    /// ie `[len, input_0, .., input_len-1]`, so empty windows cost a single slot
+    LengthPrefixed,
+}
+
+/// Splits a legacy fixed chunk input stream into one slice per input window.
+#[must_use]
+pub fn decode_fixed_chunks(input: &[u32], chunk_size: u32) -> Vec<&[u32]> {
+    input.chunks(chunk_size as usize).collect()
+}
+
+/// Splits a length prefixed input stream into exactly `windows` slices.
+///
+/// # Errors
+///
+/// Will return `Err` if the stream runs out before `windows` windows are read,
+/// if a window is longer than `max_window_len` or than the remaining stream,
+/// or if data is left over after the last window
+pub fn decode_length_prefixed(input: &[u32], windows: u32, max_window_len: u32) -> Result<Vec<&[u32]>> {
+    let mut decoded = Vec::with_capacity(windows as usize);
+    let mut rest = input;
+
+    for window in 0..windows {
+        let Some((&len, tail)) = rest.split_first() else {
+            return Err(SimulationError::MissingInputWindow {
+                window,
+                expected: windows,
+            });
+        };
+        if len > max_window_len {
+            return Err(SimulationError::InputWindowTooLong {
+                window,
+                received: len,
+                max: max_window_len,
+            });
+        }
+        if tail.len() < len as usize {
+            return Err(SimulationError::TruncatedInputWindow {
+                window,
+                declared: len,
+                available: u32::try_from(tail.len()).unwrap_or(u32::MAX),
+            });
+        }
+
+        let (window_input, tail) = tail.split_at(len as usize);
+        decoded.push(window_input);
+        rest = tail;
+    }
+
+    if !rest.is_empty() {
+        return Err(SimulationError::TrailingInput {
+            received: u32::try_from(rest.len()).unwrap_or(u32::MAX),
+        });
+    }
+    Ok(decoded)
+}
+
+/// Builds a length prefixed input stream out of per-window inputs.
+#[must_use]
+pub fn encode_length_prefixed<W: AsRef<[u32]>>(windows: &[W]) -> Vec<u32> {
+    let mut encoded = Vec::new();
+    for window in windows {
+        let window = window.as_ref();
+        // windows longer than u32::MAX can't be decoded anyway, decoding will reject them
+        encoded.push(u32::try_from(window.len()).unwrap_or(u32::MAX));
+        encoded.extend_from_slice(window);
+    }
+    encoded
+}
+
 impl GameState {
     #[must_use]
     pub fn new() -> Self {
-        let mut raft_left = Raft::new(
+        Self::build(GameConfig::default())
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if `config` fails `GameConfig::validate`
+    pub fn with_config(config: GameConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self::build(config))
+    }
+
+    fn build(config: GameConfig) -> Self {
+        let raft_left = Raft::new(
             Entity {
-                position: consts::LEFT_RAFT_INIT_POS,
+                position: config.left_raft_init_pos.clone(),
                 velocity: consts::NO_VELOCITY,
                 is_active: true,
             },
+            0,
             Style {
                 color: String::from("#FF0000"),
             },
+            &config,
         );
 
-        let mut raft_right = Raft::new(
+        let raft_right = Raft::new(
             Entity {
-                position: Position {
-                    x: consts::WORLD_MAX_X - consts::DEFAULT_RAFT_WIDTH,
-                    y: consts::RIGHT_RAFT_INIT_POS.y,
-                },
+                position: config.right_raft_init_pos.clone(),
                 velocity: consts::NO_VELOCITY,
                 is_active: true,
             },
+            1,
             Style {
                 color: String::from("#0000FF"),
             },
+            &config,
         );
 
         let left_fighter1 = RaftFighter::new(
             Entity {
                 position: Position {
-                    x: raft_left.entity.position.x + raft_left.width * 4 / 5,
-                    y: raft_left.entity.position.y + raft_left.height * 4 / 5,
+                    x: raft_left.entity.position.x + Fixed::from_uint(raft_left.width * 4 / 5),
+                    y: raft_left.entity.position.y + Fixed::from_uint(raft_left.height * 4 / 5),
                 },
                 velocity: consts::NO_VELOCITY,
                 is_active: true,
             },
             GunTypes::SMG,
-            consts::DEFAULT_RAFT_FIGHTER_WIDTH,
-            consts::DEFAULT_RAFT_FIGHTER_HEIGHT,
+            Bearings::East.angle(),
+            &config,
         );
 
         let right_fighter1 = RaftFighter::new(
             Entity {
                 position: Position {
-                    x: raft_right.entity.position.x + raft_right.width * 1 / 5,
-                    y: raft_right.entity.position.y + raft_right.height * 4 / 5,
+                    x: raft_right.entity.position.x + Fixed::from_uint(raft_right.width * 1 / 5),
+                    y: raft_right.entity.position.y + Fixed::from_uint(raft_right.height * 4 / 5),
                 },
                 velocity: consts::NO_VELOCITY,
                 is_active: true,
             },
             GunTypes::Bazooka,
-            consts::DEFAULT_RAFT_FIGHTER_WIDTH,
-            consts::DEFAULT_RAFT_FIGHTER_HEIGHT,
+            Bearings::West.angle(),
+            &config,
         );
 
         let right_fighter2 = RaftFighter::new(
             Entity {
                 position: Position {
-                    x: raft_right.entity.position.x + raft_right.width * 2 / 5,
-                    y: raft_right.entity.position.y + raft_right.height * 4 / 5,
+                    x: raft_right.entity.position.x + Fixed::from_uint(raft_right.width * 2 / 5),
+                    y: raft_right.entity.position.y + Fixed::from_uint(raft_right.height * 4 / 5),
                 },
                 velocity: consts::NO_VELOCITY,
                 is_active: true,
             },
             GunTypes::SMG,
-            consts::DEFAULT_RAFT_FIGHTER_WIDTH,
-            consts::DEFAULT_RAFT_FIGHTER_HEIGHT,
+            Bearings::West.angle(),
+            &config,
         );
 
-        raft_left.position_fighters(vec![left_fighter1]);
-        raft_right.position_fighters(vec![right_fighter1, right_fighter2]);
+        Self::assemble(
+            config,
+            vec![
+                (raft_left, vec![left_fighter1]),
+                (raft_right, vec![right_fighter1, right_fighter2]),
+            ],
+        )
+    }
 
-        Self {
-            raft_left,
-            raft_right,
-            left_projectiles: vec![],
-            right_projectiles: vec![],
+    /// Starts a match between any set of rafts, eg 2v2, free for all or against NPC boats.
+    /// Player `i` steers the raft at `rafts[i]`, which sets out with the fighters paired with it.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `config` fails `GameConfig::validate`
+    /// or if fewer than two teams have a raft afloat
+    pub fn with_rafts(config: GameConfig, rafts: Vec<(Raft, Vec<RaftFighter>)>) -> Result<Self> {
+        config.validate()?;
+        let state = Self::assemble(config, rafts);
+        if state.outcome() != MatchOutcome::InProgress {
+            return Err(SimulationError::NotEnoughTeams {});
+        }
+        Ok(state)
+    }
+
+    fn assemble(config: GameConfig, rafts: Vec<(Raft, Vec<RaftFighter>)>) -> Self {
+        let mut rng = Rng::new(config.rng_seed);
+        let environment = Environment::new(&config, &mut rng);
+        let mut state = Self {
+            rafts: Components::new(),
+            projectiles: Components::new(),
             ticks: 0,
+            rng,
+            environment,
+            config,
+            fighters: Components::new(),
+            events: vec![],
+        };
+        for (raft, fighters) in rafts {
+            let id = state.rafts.spawn(raft);
+            state.board(id, fighters);
         }
+        state
+    }
+
+    /// Puts `fighters` on board `raft`, leaving behind any that barely stand on it.
+    pub fn board(&mut self, raft: RaftId, fighters: Vec<RaftFighter>) {
+        let Some(hull) = self.rafts.get_mut(raft) else {
+            return;
+        };
+        for mut fighter in fighters {
+            if hull.can_board(&fighter) {
+                fighter.raft = raft;
+                hull.crew.push(self.fighters.spawn(fighter));
+            }
+        }
+    }
+
+    /// Returns the result of the match based on which teams still have a raft afloat.
+    #[must_use]
+    pub fn outcome(&self) -> MatchOutcome {
+        let mut afloat = self.rafts.values().filter(|r| r.is_afloat()).map(|r| r.team);
+        match afloat.next() {
+            None => MatchOutcome::Draw,
+            Some(team) if afloat.all(|other| other == team) => MatchOutcome::Won { team },
+            Some(_) => MatchOutcome::InProgress,
+        }
+    }
+
+    /// Sum of the health of every raft in `team`.
+    #[must_use]
+    pub fn team_health(&self, team: u32) -> u32 {
+        self.rafts
+            .values()
+            .filter(|r| r.team == team)
+            .map(|r| r.curr_health)
+            .sum()
+    }
+
+    /// Number of projectiles fired by `team` that are still in flight.
+    #[must_use]
+    pub fn projectile_count(&self, team: u32) -> usize {
+        self.projectiles.values().filter(|p| p.team == team).count()
+    }
+
+    /// Advances the simulation by `ticks_to_process` ticks reading one
+    /// length prefixed input stream per player, see `InputEncoding::LengthPrefixed`.
+    ///
+    /// `inputs` is indexed by `PlayerId::index`, one stream for every raft.
+    ///
+    /// # Errors
+    ///
+    /// Will return Err if input is invalid or if the match is already finished
+    pub fn tick(&mut self, ticks_to_process: u32, inputs: &[&[u32]]) -> Result<()> {
+        self.tick_with_encoding(ticks_to_process, inputs, InputEncoding::LengthPrefixed)
     }
 
+    /// Advances the simulation by `ticks_to_process` ticks.
+    ///
+    /// Processing stops early on the tick in which the match is decided,
+    /// so `self.ticks` may end up smaller than the requested end tick.
+    ///
     /// # Errors
     ///
-    /// Will return Err if input is invalid
+    /// Will return Err if input is invalid or if the match is already finished
     #[allow(clippy::too_many_lines)]
-    pub fn tick(&mut self, ticks_to_process: u32, input: &Vec<u32>) -> Result<()> {
-        let initial_tick = self.ticks;
-        let end_tick = initial_tick + ticks_to_process;
-        let inputs_needed = tick_inputs_needed(ticks_to_process);
-        let Some(input_len) = u32::try_from(input.len()).ok() else {
-            return Err(SimulationError::USizeToU32Conversion {});
-        };
+    pub fn tick_with_encoding(
+        &mut self,
+        ticks_to_process: u32,
+        inputs: &[&[u32]],
+        encoding: InputEncoding,
+    ) -> Result<()> {
+        self.events.clear();
+
+        let outcome = self.outcome();
+        if outcome != MatchOutcome::InProgress {
+            return Err(SimulationError::MatchFinished {
+                num_tick: self.ticks,
+                outcome,
+            });
+        }
 
-        if input_len != inputs_needed {
-            return Err(SimulationError::InvalidInputLength {
-                received: input_len,
-                expected: inputs_needed,
-                initial_tick,
-                end_tick,
+        if inputs.len() != self.rafts.len() {
+            return Err(SimulationError::InvalidPlayerCount {
+                received: u32::try_from(inputs.len()).unwrap_or(u32::MAX),
+                expected: u32::try_from(self.rafts.len()).unwrap_or(u32::MAX),
             });
         }
 
+        let initial_tick = self.ticks;
+        let end_tick = initial_tick + ticks_to_process;
+
         // TODO: does chunk and iterator result in same performance than manually calculating index?
-        // let input_index = (tick / consts::TICKS_PER_INPUT) as usize - current_input_index;
-        let chunked_input = input
-            .chunks(consts::TICK_INPUT_API_CHUNK_SIZE as usize)
-            .map(<[u32]>::to_vec)
-            .collect::<Vec<Vec<u32>>>();
-        let mut iter = chunked_input.iter();
+        // let input_index = (tick / self.config.ticks_per_input) as usize - current_input_index;
+        let mut player_windows = inputs
+            .iter()
+            .map(|input| {
+                self.decode_windows(input, ticks_to_process, encoding)
+                    .map(Vec::into_iter)
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         for curr_tick in initial_tick..end_tick {
-            let raft_left = &mut self.raft_left;
-            let raft_right = &mut self.raft_right;
-
             /*
              * here we first move entities then attempt to detect collision
              * this assumes that the initial state does not include collisions (which could potentially lead to invalid states)
@@ -215,151 +430,526 @@ impl GameState {
              * but its too expensive to clone all projectiles to extend this pattern
              * so you can roll back changes in case its needed
              *
-             * With rafts we do a clone of the entity to increase simplicity since there are only 2
+             * With rafts we do a clone of the entity to increase simplicity since there are only a few
              * but you could also instead do it inline with approach commented out below to avoid clones
              *
              * let (x, y, width, height) = raft_left.bounding_box();
-             * raft_left.entity.position.x = x.min(consts::WORLD_MAX_X - width);
-             * raft_left.entity.position.y = y.min(consts::WORLD_MAX_Y - height);
+             * raft_left.entity.position.x = x.min(config.world_max_x - width);
+             * raft_left.entity.position.y = y.min(config.world_max_y - height);
              *
              * these are also slightly different approaches since now the raft isnt set to
              * the max possible position and will instead remain in its prev valid position
              */
 
-            if curr_tick % consts::TICKS_PER_INPUT == 0 {
-                handle_input(iter.next(), raft_left, raft_right, curr_tick)?;
+            if curr_tick % self.config.ticks_per_input == 0 {
+                self.steer_rafts(&mut player_windows, curr_tick)?;
             }
 
-            update_raft(raft_left, curr_tick);
-            update_raft(raft_right, curr_tick);
-
-            update_fighters(
-                raft_left,
-                &mut self.left_projectiles,
-                Bearings::Northeast,
-                curr_tick,
-            );
-            update_fighters(
-                raft_right,
-                &mut self.right_projectiles,
-                Bearings::Northwest,
-                curr_tick,
-            );
-
-            update_projectiles(&mut self.left_projectiles, raft_right, curr_tick);
-            update_projectiles(&mut self.right_projectiles, raft_left, curr_tick);
+            self.move_rafts(curr_tick);
+            self.collide_rafts();
+            self.fire_weapons();
+            let origins = self.move_projectiles(curr_tick);
+            self.collide_projectiles(&origins, curr_tick);
 
             self.ticks += 1;
+
+            if self.outcome() != MatchOutcome::InProgress {
+                break;
+            }
         }
         Ok(())
     }
+
+    /// Splits one player's input stream into the windows covering `ticks_to_process` ticks.
+    fn decode_windows<'a>(
+        &self,
+        input: &'a [u32],
+        ticks_to_process: u32,
+        encoding: InputEncoding,
+    ) -> Result<Vec<&'a [u32]>> {
+        match encoding {
+            InputEncoding::FixedChunks => {
+                let inputs_needed = self.config.tick_inputs_needed(ticks_to_process)?;
+                let Some(input_len) = u32::try_from(input.len()).ok() else {
+                    return Err(SimulationError::USizeToU32Conversion {});
+                };
+
+                if input_len != inputs_needed {
+                    return Err(SimulationError::InvalidInputLength {
+                        received: input_len,
+                        expected: inputs_needed,
+                        initial_tick: self.ticks,
+                        end_tick: self.ticks + ticks_to_process,
+                    });
+                }
+                Ok(decode_fixed_chunks(input, self.config.tick_input_api_chunk_size))
+            }
+            InputEncoding::LengthPrefixed => decode_length_prefixed(
+                input,
+                self.config.input_windows_needed(ticks_to_process),
+                self.config.tick_input_api_chunk_size,
+            ),
+        }
+    }
+}
+
+/// Systems run once per tick, in the order they are declared.
+impl GameState {
+    /// Applies every player's input window for the tick to the raft they steer.
+    fn steer_rafts<'a, W>(&mut self, player_windows: &mut [W], curr_tick: u32) -> Result<()>
+    where
+        W: Iterator<Item = &'a [u32]>,
+    {
+        for (player, windows) in (0..).map(PlayerId).zip(player_windows.iter_mut()) {
+            let id = player.raft();
+            let enemy = closest_enemy(&self.rafts, id).map(Raft::center);
+            let forward = charge_direction(&self.rafts, id);
+            let raft = &mut self.rafts[id];
+            let input = windows.next();
+            let fighters = &mut self.fighters;
+            handle_input(forward, enemy.as_ref(), input, raft, fighters, &self.config, curr_tick)?;
+        }
+        Ok(())
+    }
+
+    fn move_rafts(&mut self, curr_tick: u32) {
+        for raft in self.rafts.values_mut() {
+            update_raft(raft, &mut self.fighters, &self.environment, &self.config, curr_tick);
+        }
+    }
+
+    /// Pushes overlapping rafts apart and damages opposing rafts that ran into each other.
+    fn collide_rafts(&mut self) {
+        let ids: Vec<RaftId> = self.rafts.ids().collect();
+        for (second, &b) in ids.iter().enumerate().skip(1) {
+            for &a in &ids[..second] {
+                let Some((a, b)) = self.rafts.get_pair_mut(a, b) else {
+                    continue;
+                };
+                if a.is_afloat() && b.is_afloat() && a.collides_with(b) {
+                    collide_rafts(a, b, &mut self.fighters, &mut self.rng, &self.config);
+                }
+            }
+        }
+    }
+
+    /// Ticks every weapon, fires the selected fighters of rafts that asked for it and takes
+    /// fighters that were knocked out off their raft.
+    fn fire_weapons(&mut self) {
+        let fighters = &mut self.fighters;
+        for raft in self.rafts.values_mut() {
+            if raft.entity.is_active {
+                for &id in &raft.crew {
+                    if let Some(fighter) = fighters.get_mut(id) {
+                        fighter.update_weapon();
+                    }
+                }
+
+                if raft.fire_requested {
+                    if let Some(fighter) = raft.selected_fighter_mut(fighters) {
+                        for projectile in fighter.fire(raft.team, &self.config) {
+                            self.projectiles.spawn(projectile);
+                        }
+                    }
+                }
+
+                raft.remove_knocked_out(fighters);
+            }
+            raft.fire_requested = false;
+        }
+    }
+
+    /// Moves every projectile and returns where each one started from.
+    fn move_projectiles(&mut self, curr_tick: u32) -> Vec<(ProjectileId, Position)> {
+        self.projectiles
+            .iter_mut()
+            .map(|(id, projectile)| {
+                let origin = projectile.entity.position.clone();
+                projectile.update_position(curr_tick, self.config.gravity, &self.environment);
+                projectile.age += 1;
+                (id, origin)
+            })
+            .collect()
+    }
+
+    /// Resolves what each projectile hit on its way from its origin, then despawns the ones
+    /// that are spent, out of range or out of the world.
+    fn collide_projectiles(&mut self, origins: &[(ProjectileId, Position)], curr_tick: u32) {
+        if origins.is_empty() {
+            return;
+        }
+        let grid = target_grid(&self.rafts, &self.fighters, self.config.grid_cell_size);
+
+        for (id, origin) in origins {
+            let Some(item) = self.projectiles.get_mut(*id) else {
+                continue;
+            };
+            let spec = item.gun.spec();
+            let direct_hit = item.hit();
+            let mut impact = None;
+
+            // the first contact along the path takes the hit, piercing projectiles go on
+            // to the first contact of every other raft they pass through
+            for (entry, target) in contacts(item, origin, &self.rafts, &self.fighters, &grid) {
+                if item.hit_rafts.contains(&target.raft()) {
+                    continue;
+                }
+                let damage = damage_target(
+                    &mut self.rafts,
+                    &mut self.fighters,
+                    target,
+                    &direct_hit,
+                    &mut self.rng,
+                );
+                self.events.push(GameEvent::Hit(HitEvent {
+                    tick: curr_tick,
+                    team: item.team,
+                    gun: item.gun,
+                    target,
+                    damage,
+                    splash: false,
+                }));
+                impact = Some((target, item.position_along(origin, entry)));
+
+                if spec.piercing {
+                    item.hit_rafts.push(target.raft());
+                } else {
+                    item.entity.is_active = false;
+                    break;
+                }
+            }
+
+            if spec.range > 0 && item.age >= spec.range {
+                item.entity.is_active = false;
+            }
+
+            // Check if the projectile is within world bounds
+            if !is_within_world_bounds(item, &self.config) {
+                item.entity.is_active = false;
+            }
+
+            if let Some((direct, contact)) = impact {
+                if spec.splash_radius_multiplier > 0 {
+                    let projectile = item.clone();
+                    apply_splash(self, &projectile, direct, &contact, curr_tick);
+                }
+            }
+        }
+
+        self.projectiles.retain(|_, p| p.entity.is_active);
+    }
 }
 
 fn handle_input(
-    input_for_tick: Option<&Vec<u32>>,
-    raft_left: &mut Raft,
-    raft_right: &mut Raft,
+    forward: i32,
+    enemy: Option<&Position>,
+    input_for_tick: Option<&[u32]>,
+    raft: &mut Raft,
+    fighters: &mut Components<FighterId, RaftFighter>,
+    config: &GameConfig,
     curr_tick: u32,
 ) -> Result<()> {
     let input_for_tick = input_for_tick.ok_or(SimulationError::NoInput {
         num_tick: curr_tick,
     })?;
 
+    let gain = config.velocity_gain_normal;
+    let aim_step = Angle::from_steps(i64::from(config.aim_step));
     for &curr in input_for_tick {
-        match GameInput::from(curr)? {
-            GameInput::NoOp => {}
-            GameInput::DeprecatedShootLeftRaft => {}
-            GameInput::DeprecatedShootRightRaft => {}
-            GameInput::MoveLeftRaftRight => {
-                raft_left.entity.velocity.vx = consts::VELOCITY_GAIN_NORMAL;
-            }
-            GameInput::MoveLeftRaftLeft => {
-                raft_left.entity.velocity.vx = -consts::VELOCITY_GAIN_NORMAL;
-            }
-            GameInput::MoveRightRaftRight => {
-                raft_right.entity.velocity.vx = consts::VELOCITY_GAIN_NORMAL;
-            }
-            GameInput::MoveUpRaftRight => {
-                raft_right.entity.velocity.vy = consts::VELOCITY_GAIN_NORMAL;
+        match Action::from(curr)? {
+            Action::NoOp => {}
+            Action::Fire => raft.fire_requested = true,
+            Action::SelectNextFighter => raft.select_next_fighter(),
+            // angles grow counter-clockwise
+            Action::AimClockwise => {
+                raft.aim_selected_fighter(fighters, |fighter| fighter.aim - aim_step);
             }
-            GameInput::MoveRightRaftLeft => {
-                raft_right.entity.velocity.vx = -consts::VELOCITY_GAIN_NORMAL;
+            Action::AimCounterClockwise => {
+                raft.aim_selected_fighter(fighters, |fighter| fighter.aim + aim_step);
             }
-            GameInput::MoveLeftFastRaftLeft => {
-                raft_left.entity.velocity.vx = consts::VELOCITY_GAIN_BOOST;
+            Action::AimAtEnemy => {
+                if let Some(enemy) = enemy {
+                    raft.aim_selected_fighter(fighters, |fighter| {
+                        Angle::towards(&fighter.center(), enemy)
+                    });
+                }
             }
-            GameInput::MoveLeftFastRaftRight => {
-                raft_right.entity.velocity.vx = -consts::VELOCITY_GAIN_BOOST;
+            Action::MoveRight => raft.paddle(gain, 0, config),
+            Action::MoveLeft => raft.paddle(-gain, 0, config),
+            Action::MoveUp => raft.paddle(0, gain, config),
+            Action::MoveDown => raft.paddle(0, -gain, config),
+            Action::Charge => {
+                let boost = raft.impulse(forward * config.velocity_gain_boost, config);
+                raft.entity.velocity.vx = raft.entity.velocity.vx.saturating_add(boost);
             }
-            GameInput::DeprecatedAddProjectile => {}
-            GameInput::MoveRightRaftDown => {
-                raft_right.entity.velocity.vy = -consts::VELOCITY_GAIN_NORMAL;
-            }
-            GameInput::MoveUpRaftLeft => {
-                raft_left.entity.velocity.vy = consts::VELOCITY_GAIN_NORMAL;
-            }
-            GameInput::MoveLeftRaftDown => {
-                raft_left.entity.velocity.vy = -consts::VELOCITY_GAIN_NORMAL;
+            Action::Ram => {
+                // a raft can't ram again before its current ram is over
+                if raft.ram_ticks == 0 {
+                    let boost = raft.impulse(forward * config.velocity_gain_boost, config);
+                    raft.entity.velocity.vx = raft.entity.velocity.vx.saturating_add(boost);
+                    raft.ram_ticks = config.ram_duration;
+                }
             }
         };
     }
     Ok(())
 }
 
-fn update_raft(raft: &mut Raft, curr_tick: u32) {
-    let prev_entity = raft.entity.clone();
-    raft.update_position(curr_tick);
+/// Closest raft of another team than raft `id` that is still afloat, by horizontal distance.
+fn closest_enemy(rafts: &Components<RaftId, Raft>, id: RaftId) -> Option<&Raft> {
+    let raft = &rafts[id];
+    let x = raft.entity.position.x;
+    rafts
+        .values()
+        .filter(|other| other.team != raft.team && other.is_afloat())
+        .min_by_key(|other| (other.entity.position.x - x).abs())
+}
 
-    if !is_within_world_bounds(raft) {
-        raft.entity = prev_entity;
-    }
+/// Horizontal direction from raft `id` towards `closest_enemy`.
+fn charge_direction(rafts: &Components<RaftId, Raft>, id: RaftId) -> i32 {
+    let x = rafts[id].entity.position.x;
+    closest_enemy(rafts, id).map_or(1, |other| if other.entity.position.x < x { -1 } else { 1 })
 }
 
-fn update_fighters(
+fn update_raft(
     raft: &mut Raft,
-    projectiles: &mut Vec<Projectile>,
-    direction: Bearings,
+    fighters: &mut Components<FighterId, RaftFighter>,
+    environment: &Environment,
+    config: &GameConfig,
     curr_tick: u32,
 ) {
-    if raft.entity.is_active {
-        for fighter in &raft.raft_fighters {
-            let fire_rate = fighter.gun.fire_rate();
-            if curr_tick % fire_rate == 0 {
-                let proj = fighter.create_projectile(direction);
-                projectiles.push(proj);
-            }
+    let prev_entity = raft.entity.clone();
+    raft.update_position(fighters, curr_tick, config.raft_drag, environment);
+    raft.ram_ticks = raft.ram_ticks.saturating_sub(1);
+    // a ramming raft may cross its border, once the ram is over it paddles back
+    if raft.ram_ticks == 0 {
+        raft.return_to_zone(fighters, config.raft_max_speed);
+    }
+
+    if !is_within_world_bounds(raft, config) {
+        // running into the edge of the world stops the raft
+        raft.entity = prev_entity;
+        raft.entity.velocity = consts::NO_VELOCITY;
+
+        // a raft blocked by the edge of the world still rides the tide, otherwise it drifts out of sync
+        let blocked_entity = raft.entity.clone();
+        raft.entity.position.y += Fixed::from_int(environment.tide_delta(curr_tick));
+        if !is_within_world_bounds(raft, config) {
+            raft.entity = blocked_entity;
         }
+    }
+}
+
+/// Separates `a` and `b` along the axis they overlap least on and merges their velocities
+/// along it, as if the rafts stuck together for the impact.
+fn collide_rafts(
+    a: &mut Raft,
+    b: &mut Raft,
+    fighters: &mut Components<FighterId, RaftFighter>,
+    rng: &mut Rng,
+    config: &GameConfig,
+) {
+    let (ax, ay, aw, ah) = a.bounding_box();
+    let (bx, by, bw, bh) = b.bounding_box();
+    let overlap_x = (ax + aw).min(bx + bw) - ax.max(bx);
+    let overlap_y = (ay + ah).min(by + bh) - ay.max(by);
+    let along_x = overlap_x <= overlap_y;
+
+    // overlaps are at most a raft's size, which fits in an i32 for valid configs
+    let overlap = i32::try_from(if along_x { overlap_x } else { overlap_y }).unwrap_or(0);
+    let a_first = if along_x { ax <= bx } else { ay <= by };
+    let push = if a_first { -overlap } else { overlap };
+    let (push_a, push_b) = (Fixed::from_int(push / 2), Fixed::from_int(push / 2 - push));
+    let (speed_a, speed_b) = if along_x {
+        a.move_by(fighters, push_a, Fixed::ZERO);
+        b.move_by(fighters, push_b, Fixed::ZERO);
+        (a.entity.velocity.vx, b.entity.velocity.vx)
+    } else {
+        a.move_by(fighters, Fixed::ZERO, push_a);
+        b.move_by(fighters, Fixed::ZERO, push_b);
+        (a.entity.velocity.vy, b.entity.velocity.vy)
+    };
+
+    let closing_speed = (speed_a - speed_b).abs();
+    let merged = merged_speed(speed_a, a.mass, speed_b, b.mass);
+    if along_x {
+        a.entity.velocity.vx = merged;
+        b.entity.velocity.vx = merged;
+    } else {
+        a.entity.velocity.vy = merged;
+        b.entity.velocity.vy = merged;
+    }
 
-        raft.raft_fighters.retain(|f| f.entity.is_active);
+    if a.team != b.team {
+        let hit_a = Hit::new(ram_damage(closing_speed, b, config), DamageType::Impact);
+        let hit_b = Hit::new(ram_damage(closing_speed, a, config), DamageType::Impact);
+        a.take_damage(&hit_a, rng);
+        b.take_damage(&hit_b, rng);
     }
 }
 
-fn update_projectiles(projectiles: &mut Vec<Projectile>, opposing_raft: &mut Raft, curr_tick: u32) {
-    for item in projectiles.iter_mut() {
-        item.update_position(curr_tick);
+/// Common speed of two rafts after a collision, weighted by their mass.
+fn merged_speed(speed_a: Fixed, mass_a: u32, speed_b: Fixed, mass_b: u32) -> Fixed {
+    let momentum = i128::from(speed_a.to_bits()) * i128::from(mass_a)
+        + i128::from(speed_b.to_bits()) * i128::from(mass_b);
+    let total_mass = i128::from(mass_a) + i128::from(mass_b);
+    // a weighted average of two speeds lies between them, so it always fits
+    Fixed::from_bits(i64::try_from(momentum / total_mass.max(1)).unwrap_or(0))
+}
 
-        // Check for collisions with opposing raft fighters
-        for fighter in &mut opposing_raft.raft_fighters {
-            if item.collides_with(fighter) {
-                fighter.take_damage(item);
-                item.entity.is_active = false;
+/// Damage `rammer` deals when hitting a raft at `closing_speed`, heavier and ramming rafts hit harder.
+fn ram_damage(closing_speed: Fixed, rammer: &Raft, config: &GameConfig) -> u32 {
+    let boost = if rammer.ram_ticks > 0 {
+        config.ram_boost_multiplier
+    } else {
+        1
+    };
+    let scale = i64::from(config.ram_damage) * i64::from(boost) * i64::from(rammer.mass);
+    closing_speed
+        .mul_div(scale, i64::from(config.standard_raft_mass))
+        .to_u32()
+}
+
+/// Files every raft and fighter in a broadphase grid for this tick's projectiles.
+fn target_grid(
+    rafts: &Components<RaftId, Raft>,
+    fighters: &Components<FighterId, RaftFighter>,
+    cell_size: u32,
+) -> SpatialGrid<HitTarget> {
+    let mut grid = SpatialGrid::new(cell_size);
+    for (raft_id, raft) in rafts.iter() {
+        grid.insert(HitTarget::Raft { raft: raft_id }, raft.bounding_box());
+        for &id in &raft.crew {
+            if let Some(fighter) = fighters.get(id) {
+                let target = HitTarget::Fighter {
+                    raft: raft_id,
+                    fighter: id,
+                };
+                grid.insert(target, fighter.bounding_box());
             }
         }
+    }
+    grid
+}
 
-        // Check for collisions with the opposing raft
-        if item.collides_with(opposing_raft) {
-            opposing_raft.take_damage(item);
-            item.entity.is_active = false;
+/// Opposing targets `projectile` swept through on its way from `origin`, in the order it
+/// reached them, with when it did in `SWEEP_ONE`ths of its move.
+///
+/// Only targets `grid` files near the projectile's path are checked. Each target is ordered
+/// by when the projectile entered it, ties fall back to `HitTarget`'s order so a fighter
+/// shields the hull it stands in front of and the result is always the same.
+fn contacts(
+    projectile: &Projectile,
+    origin: &Position,
+    rafts: &Components<RaftId, Raft>,
+    fighters: &Components<FighterId, RaftFighter>,
+    grid: &SpatialGrid<HitTarget>,
+) -> Vec<(u32, HitTarget)> {
+    let mut swept = vec![];
+    for target in grid.query(projectile.swept_box(origin)) {
+        let Some(raft) = rafts.get(target.raft()) else {
+            continue;
+        };
+        if raft.team == projectile.team || !raft.is_afloat() {
+            continue;
+        }
+        let entry = match target {
+            HitTarget::Fighter { fighter, .. } => match fighters.get(fighter) {
+                Some(fighter) if fighter.entity.is_active => projectile.sweep(origin, fighter),
+                _ => continue,
+            },
+            HitTarget::Raft { .. } => projectile.sweep(origin, raft),
+        };
+        if let Some(entry) = entry {
+            swept.push((entry, target));
         }
+    }
+
+    // on a tie the fighter is hit before the hull behind it, see `HitTarget`'s order
+    swept.sort_unstable();
+    swept
+}
 
-        // Check if the projectile is within world bounds
-        if !is_within_world_bounds(item) {
-            item.entity.is_active = false;
+/// Applies `hit` to `target` and returns the damage it took.
+fn damage_target(
+    rafts: &mut Components<RaftId, Raft>,
+    fighters: &mut Components<FighterId, RaftFighter>,
+    target: HitTarget,
+    hit: &Hit,
+    rng: &mut Rng,
+) -> u32 {
+    match target {
+        HitTarget::Raft { raft } => rafts.get_mut(raft).map_or(0, |raft| raft.take_damage(hit, rng)),
+        HitTarget::Fighter { fighter, .. } => fighters
+            .get_mut(fighter)
+            .map_or(0, |fighter| fighter.take_damage(hit, rng)),
+    }
+}
+
+/// Damages every opposing raft and fighter within reach of `center`, where `projectile`
+/// hit its `direct` target, besides that target.
+fn apply_splash(
+    state: &mut GameState,
+    projectile: &Projectile,
+    direct: HitTarget,
+    center: &Position,
+    curr_tick: u32,
+) {
+    let spec = projectile.gun.spec();
+    let hit = Hit::new(spec.splash_damage, spec.damage_type);
+    let radius = spec.splash_radius_multiplier * state.config.projectile_radius;
+
+    let mut caught = vec![];
+    for (raft_id, raft) in state.rafts.iter() {
+        // sunk rafts and their crew are out of the fight, same as for direct hits
+        if raft.team == projectile.team || !raft.is_afloat() {
+            continue;
+        }
+        for &id in &raft.crew {
+            let in_reach = |f: &RaftFighter| f.entity.is_active && within_radius(center, f, radius);
+            if state.fighters.get(id).is_some_and(in_reach) {
+                caught.push(HitTarget::Fighter {
+                    raft: raft_id,
+                    fighter: id,
+                });
+            }
+        }
+        if within_radius(center, raft, radius) {
+            caught.push(HitTarget::Raft { raft: raft_id });
         }
     }
 
-    // Retain only active projectiles
-    projectiles.retain(|p| p.entity.is_active);
+    for target in caught.into_iter().filter(|&target| target != direct) {
+        let damage = damage_target(
+            &mut state.rafts,
+            &mut state.fighters,
+            target,
+            &hit,
+            &mut state.rng,
+        );
+        state.events.push(GameEvent::Hit(HitEvent {
+            tick: curr_tick,
+            team: projectile.team,
+            gun: projectile.gun,
+            target,
+            damage,
+            splash: true,
+        }));
+    }
+}
+
+/// Squared distance from `point` to the closest point of `obj`'s bounding box.
+fn distance_squared<T: Collision>(point: &Position, obj: &T) -> u64 {
+    let (x, y, width, height) = obj.bounding_box();
+    let (px, py) = (point.x.to_u32(), point.y.to_u32());
+    let dx = u64::from(x.saturating_sub(px).max(px.saturating_sub(x + width)));
+    let dy = u64::from(y.saturating_sub(py).max(py.saturating_sub(y + height)));
+    dx * dx + dy * dy
+}
+
+/// Checks whether the closest point of `obj`'s bounding box is at most `radius` away from `center`.
+fn within_radius<T: Collision>(center: &Position, obj: &T, radius: u32) -> bool {
+    distance_squared(center, obj) <= u64::from(radius) * u64::from(radius)
 }
 
 impl Default for GameState {
@@ -368,168 +958,280 @@ impl Default for GameState {
     }
 }
 
+/// Launch profile of a shot, picked from the way the fighter is aiming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProjectileDirection {
     ArchRight,
     ArchLeft,
     StraightDown,
 }
 
-const fn tick_inputs_needed(ticks_to_process: u32) -> u32 {
-    (ticks_to_process / consts::TICKS_PER_INPUT
-        + if ticks_to_process % consts::TICKS_PER_INPUT > 0 {
-            1
-        } else {
-            0
-        })
-        * consts::TICK_INPUT_API_CHUNK_SIZE
+impl ProjectileDirection {
+    /// Shots aimed with a horizontal component arc that way, anything else is dropped straight down.
+    #[must_use]
+    pub const fn from_aim(aim: Angle) -> Self {
+        let (dx, _) = aim.direction();
+        match dx.to_bits().signum() {
+            1 => Self::ArchRight,
+            -1 => Self::ArchLeft,
+            _ => Self::StraightDown,
+        }
+    }
+
+    /// Initial velocity of a projectile fired along `aim` with `spec`.
+    ///
+    /// Arcing shots get the gun's lift on top of the aim so gravity brings them down
+    /// over the water, aiming up or down steepens or flattens the arc.
+    #[must_use]
+    pub const fn launch_velocity(&self, aim: Angle, spec: &WeaponSpec) -> Velocity {
+        let (dx, dy) = aim.direction();
+        let speed = Fixed::from_int(spec.speed);
+        match self {
+            Self::ArchRight | Self::ArchLeft => Velocity {
+                vx: speed.saturating_mul(dx),
+                vy: Fixed::from_int(spec.lift).saturating_add(speed.saturating_mul(dy)),
+            },
+            Self::StraightDown => Velocity {
+                vx: Fixed::ZERO,
+                vy: Fixed::from_int(-spec.speed),
+            },
+        }
+    }
 }
 
-fn is_within_world_bounds<T: Collision>(obj: &T) -> bool {
+fn is_within_world_bounds<T: Collision>(obj: &T, config: &GameConfig) -> bool {
     let (x, y, width, height) = obj.bounding_box();
     x > 0
         && y > 0
-        && x.saturating_add(width) <= consts::WORLD_MAX_X
-        && y.saturating_add(height) <= consts::WORLD_MAX_Y
+        && x.saturating_add(width) <= config.world_max_x
+        && y.saturating_add(height) <= config.world_max_y
 }
 
 impl Raft {
-    pub fn position_fighters(&mut self, fighters: Vec<RaftFighter>) {
-        for fighter in fighters {
-            let (fx, fy, fw, fh) = fighter.bounding_box();
-            let (rx, ry, rw, rh) = self.bounding_box();
+    #[must_use]
+    pub const fn is_afloat(&self) -> bool {
+        self.entity.is_active && self.curr_health > 0
+    }
 
-            // Calculate the overlapping area
-            let overlap_x = (fx.max(rx) as i32 - (fx + fw).min(rx + rw) as i32).abs() as u32;
-            let overlap_y = (fy.max(ry) as i32 - (fy + fh).min(ry + rh) as i32).abs() as u32;
+    /// Whether `fighter` stands far enough on the raft to board it.
+    #[must_use]
+    pub fn can_board(&self, fighter: &RaftFighter) -> bool {
+        let (fx, fy, fw, fh) = fighter.bounding_box();
+        let (rx, ry, rw, rh) = self.bounding_box();
 
-            // Calculate the area of the fighter
-            let fighter_area = fw * fh;
+        // Calculate the overlapping area
+        let overlap_x = (fx.max(rx) as i32 - (fx + fw).min(rx + rw) as i32).abs() as u32;
+        let overlap_y = (fy.max(ry) as i32 - (fy + fh).min(ry + rh) as i32).abs() as u32;
 
-            // Calculate the overlapping area
-            let overlap_area = overlap_x * overlap_y;
+        // Calculate the area of the fighter
+        let fighter_area = fw * fh;
 
-            // Check if the overlap area is at least a certain percentage of the fighter's area
-            if overlap_area * 100 >= fighter_area * 5 {
-                // Assuming 5% overlap is required
-                self.raft_fighters.push(fighter.clone());
-            }
-        }
+        // Calculate the overlapping area
+        let overlap_area = overlap_x * overlap_y;
+
+        // Check if the overlap area is at least a certain percentage of the fighter's area
+        // Assuming 5% overlap is required
+        overlap_area * 100 >= fighter_area * 5
     }
 
-    pub fn take_damage(&mut self, projectile: &Projectile) {
-        if self.curr_health > 0 {
-            let perc = self.max_health / self.curr_health;
-            let dmg = projectile.radius * perc;
+    /// Returns the fighter currently controlled by the player, if it is still alive.
+    #[must_use]
+    pub fn selected_fighter<'a>(
+        &self,
+        fighters: &'a Components<FighterId, RaftFighter>,
+    ) -> Option<&'a RaftFighter> {
+        self.crew
+            .get(self.selected_fighter as usize)
+            .and_then(|&id| fighters.get(id))
+            .filter(|f| f.entity.is_active)
+    }
 
-            self.curr_health = self.curr_health.saturating_sub(dmg);
+    pub fn selected_fighter_mut<'a>(
+        &self,
+        fighters: &'a mut Components<FighterId, RaftFighter>,
+    ) -> Option<&'a mut RaftFighter> {
+        self.crew
+            .get(self.selected_fighter as usize)
+            .and_then(|&id| fighters.get_mut(id))
+            .filter(|f| f.entity.is_active)
+    }
 
-            if self.curr_health == 0 {
-                self.entity.is_active = false;
+    /// Cycles control to the next fighter on the raft, wrapping around at the end.
+    pub fn select_next_fighter(&mut self) {
+        let Ok(num_fighters) = u32::try_from(self.crew.len()) else {
+            return;
+        };
+        if num_fighters > 0 {
+            self.selected_fighter = (self.selected_fighter + 1) % num_fighters;
+        }
+    }
+
+    /// Takes fighters that were knocked out off the raft and despawns them.
+    ///
+    /// The selection stays on the same fighter, or moves on to the next one still on board
+    /// if the selected fighter was knocked out.
+    pub fn remove_knocked_out(&mut self, fighters: &mut Components<FighterId, RaftFighter>) {
+        let selected = self.selected_fighter as usize;
+        let (mut index, mut removed_before) = (0, 0);
+        self.crew.retain(|&id| {
+            let active = fighters.get(id).is_some_and(|f| f.entity.is_active);
+            if !active {
+                fighters.despawn(id);
+                removed_before += u32::from(index < selected);
             }
+            index += 1;
+            active
+        });
+
+        let num_fighters = u32::try_from(self.crew.len()).unwrap_or(u32::MAX);
+        self.selected_fighter = self
+            .selected_fighter
+            .saturating_sub(removed_before)
+            .checked_rem(num_fighters)
+            .unwrap_or(0);
+    }
+
+    /// Points the selected fighter along the angle `aim` picks for it.
+    pub fn aim_selected_fighter(
+        &self,
+        fighters: &mut Components<FighterId, RaftFighter>,
+        aim: impl FnOnce(&RaftFighter) -> Angle,
+    ) {
+        let selected = self.crew.get(self.selected_fighter as usize);
+        if let Some(fighter) = selected.and_then(|&id| fighters.get_mut(id)) {
+            fighter.aim = aim(fighter);
         }
     }
 }
 
 impl RaftFighter {
-    pub fn take_damage(&mut self, projectile: &Projectile) {
-        if self.curr_health > 0 {
-            let perc = self.max_health / self.curr_health;
-            let dmg = projectile.radius * perc;
+    /// Advances the weapon cooldown and reload timers by one tick.
+    pub fn update_weapon(&mut self) {
+        let weapon = &mut self.weapon;
+        weapon.cooldown = weapon.cooldown.saturating_sub(1);
+
+        if weapon.reload > 0 {
+            weapon.reload -= 1;
+            if weapon.reload == 0 {
+                weapon.magazine = self.gun.spec().magazine_size;
+            }
+        }
+    }
+
+    #[must_use]
+    pub const fn can_fire(&self) -> bool {
+        self.entity.is_active
+            && self.weapon.cooldown == 0
+            && self.weapon.reload == 0
+            && self.weapon.magazine > 0
+    }
 
-            self.curr_health = self.curr_health.saturating_sub(dmg);
+    /// Fires along the current aim if the weapon is ready, starting the cooldown
+    /// and a reload once the magazine is empty. Returns one projectile per pellet.
+    pub fn fire(&mut self, team: u32, config: &GameConfig) -> Vec<Projectile> {
+        if !self.can_fire() {
+            return vec![];
+        }
 
-            if self.curr_health == 0 {
-                self.entity.is_active = false;
-            }
+        let spec = self.gun.spec();
+        self.weapon.cooldown = spec.fire_rate;
+        self.weapon.magazine -= 1;
+        if self.weapon.magazine == 0 {
+            self.weapon.reload = spec.reload_time;
         }
+
+        self.create_projectiles(self.aim, team, config)
     }
 
-    pub fn create_projectile(&self, side: Bearings) -> Projectile {
-        let (radius, base_velocity) = match self.gun {
-            GunTypes::Bazooka => (consts::DEFAULT_PROJECTILE_RADIUS * 2, 5),
-            GunTypes::SMG => (consts::DEFAULT_PROJECTILE_RADIUS, 10),
-            GunTypes::FlameThrower => (consts::DEFAULT_PROJECTILE_RADIUS, 8),
-            GunTypes::StraightShooter => (consts::DEFAULT_PROJECTILE_RADIUS, 12),
+    /// Spreads the gun's pellets evenly in a cone around `aim`.
+    #[must_use]
+    pub fn create_projectiles(&self, aim: Angle, team: u32, config: &GameConfig) -> Vec<Projectile> {
+        let spec = self.gun.spec();
+        let proto = self.create_projectile(aim, team, config);
+        let (dx, dy) = aim.direction();
+        let Ok(pellets) = i32::try_from(spec.pellets) else {
+            return vec![proto];
         };
 
-        let style = self.gun.style();
+        (0..pellets)
+            .map(|pellet| {
+                // offset perpendicular to the aim, centered on it
+                let offset = Fixed::from_int((2 * pellet - (pellets - 1)) * spec.pellet_spread / 2);
+                let mut projectile = proto.clone();
+                projectile.entity.velocity.vx -= offset.saturating_mul(dy);
+                projectile.entity.velocity.vy += offset.saturating_mul(dx);
+                projectile
+            })
+            .collect()
+    }
 
-        let init_pos = match side {
-            Bearings::East => Position {
-                x: self.entity.position.x + self.width + consts::DEFAULT_PROJECTILE_RADIUS * 2,
-                y: self.entity.position.y + self.height + consts::DEFAULT_PROJECTILE_RADIUS * 2,
-            },
-            Bearings::West => Position {
-                x: self
-                    .entity
-                    .position
-                    .x
-                    .saturating_sub(consts::DEFAULT_PROJECTILE_RADIUS),
-                y: self.entity.position.y + self.height + consts::DEFAULT_PROJECTILE_RADIUS * 2,
-            },
-            _ => Position {
-                x: self.entity.position.x,
-                y: self.entity.position.y,
-            },
+    /// Single shot fired along `aim`, which can be any angle and not just a `Bearings`.
+    #[must_use]
+    pub fn create_projectile(&self, aim: Angle, team: u32, config: &GameConfig) -> Projectile {
+        let base_radius = config.projectile_radius;
+        let spec = self.gun.spec();
+        let radius = base_radius * spec.radius_multiplier;
+
+        // spawn just outside the fighter in the direction it is aiming
+        let (dx, dy) = aim.direction();
+        let position = &self.entity.position;
+        let init_x = match dx.to_bits().signum() {
+            1 => position.x + Fixed::from_uint(self.width + base_radius * 2),
+            -1 => position.x - Fixed::from_uint(base_radius),
+            _ => position.x + Fixed::from_uint(self.width / 2),
         };
-
-        let velocity = match side {
-            Bearings::North => Velocity {
-                vx: 0,
-                vy: -base_velocity,
-            },
-            Bearings::South => Velocity {
-                vx: 0,
-                vy: base_velocity,
-            },
-            Bearings::East => Velocity {
-                vx: base_velocity,
-                vy: 0,
-            },
-            Bearings::West => Velocity {
-                vx: -base_velocity,
-                vy: 0,
-            },
-            Bearings::Northeast => Velocity {
-                vx: base_velocity,
-                vy: -base_velocity,
-            },
-            Bearings::Northwest => Velocity {
-                vx: -base_velocity,
-                vy: -base_velocity,
-            },
-            Bearings::Southeast => Velocity {
-                vx: base_velocity,
-                vy: base_velocity,
-            },
-            Bearings::Southwest => Velocity {
-                vx: -base_velocity,
-                vy: base_velocity,
-            },
+        let init_y = match dy.to_bits().signum() {
+            -1 => position.y - Fixed::from_uint(base_radius * 2),
+            _ => position.y + Fixed::from_uint(self.height + base_radius * 2),
+        };
+        let init_pos = Position {
+            x: init_x,
+            y: init_y,
         };
 
-        Projectile {
-            radius,
-            entity: Entity {
+        let velocity = ProjectileDirection::from_aim(aim).launch_velocity(aim, spec);
+
+        Projectile::new(
+            Entity {
                 position: init_pos,
-                velocity: velocity,
+                velocity,
                 is_active: true,
             },
-            style,
-        }
+            radius,
+            self.gun,
+            team,
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::world::WeaponState;
     use strum::IntoEnumIterator;
 
+    fn window(actions: &[Action]) -> Vec<u32> {
+        encode_length_prefixed(&[actions.iter().map(Action::to_u32).collect::<Vec<u32>>()])
+    }
+
+    fn repeat_window(actions: &[Action], windows: u32) -> Vec<u32> {
+        (0..windows).flat_map(|_| window(actions)).collect()
+    }
+
+    fn raft(player: u32) -> RaftId {
+        PlayerId(player).raft()
+    }
+
+    fn crew(state: &GameState, player: u32) -> Vec<&RaftFighter> {
+        let crew = &state.rafts[raft(player)].crew;
+        crew.iter().map(|&id| &state.fighters[id]).collect()
+    }
+
     #[test]
     fn it_exhaustively_handles_all_game_iputs_from_() {
-        for variant in GameInput::iter() {
+        for variant in Action::iter() {
             let as_u32 = variant.to_u32();
-            let from_u32 = GameInput::from(as_u32);
+            let from_u32 = Action::from(as_u32);
             assert!(from_u32.is_ok());
             assert_eq!(from_u32.unwrap(), variant);
         }
@@ -539,77 +1241,1101 @@ mod tests {
     fn it_creates_stable_game_state() {
         let mut state = GameState::new();
         let ticks = 1000;
-        let inputs_needed = tick_inputs_needed(ticks);
-        let inputs = vec![GameInput::NoOp.to_u32(); inputs_needed as usize];
+        let inputs = repeat_window(&[], state.config.input_windows_needed(ticks));
         let initial_state = state.clone();
 
-        state.tick(ticks, &inputs).unwrap();
+        state.tick(ticks, &[&inputs, &inputs]).unwrap();
 
         assert_eq!(
-            initial_state.raft_left.entity.position.x,
-            state.raft_left.entity.position.x
+            initial_state.rafts[raft(0)].entity.position.x,
+            state.rafts[raft(0)].entity.position.x
         );
+        // idle rafts only ride the tide
+        let ebb = Fixed::from_uint(state.environment.tide_level(0) - state.environment.tide_level(ticks));
         assert_eq!(
-            initial_state.raft_left.entity.position.y,
-            state.raft_left.entity.position.y
+            initial_state.rafts[raft(0)].entity.position.y - ebb,
+            state.rafts[raft(0)].entity.position.y
         );
         assert_eq!(
-            initial_state.raft_right.entity.position.x,
-            state.raft_right.entity.position.x
+            initial_state.rafts[raft(1)].entity.position.x,
+            state.rafts[raft(1)].entity.position.x
         );
         assert_eq!(
-            initial_state.raft_right.entity.position.y,
-            state.raft_right.entity.position.y
+            initial_state.rafts[raft(1)].entity.position.y - ebb,
+            state.rafts[raft(1)].entity.position.y
         );
     }
 
-    #[test]
+    /// Fixed chunk input streams for both players, the slot a player
+    /// doesn't act in is padded with `Action::NoOp` so both streams stay aligned.
     #[allow(clippy::match_same_arms)]
-    fn it_simulates_game_state() {
-        let mut state = GameState::new();
-        let ticks = 10000;
-        let inputs_needed = tick_inputs_needed(ticks);
-        let mut inputs = Vec::with_capacity(inputs_needed as usize);
+    fn simulated_inputs(inputs_needed: u32) -> [Vec<u32>; 2] {
+        let mut inputs = [
+            Vec::with_capacity(inputs_needed as usize),
+            Vec::with_capacity(inputs_needed as usize),
+        ];
 
         for i in 0..inputs_needed {
-            inputs.push(match i % 33 {
-                0 => GameInput::DeprecatedShootLeftRaft.to_u32(),
-                1 => GameInput::MoveLeftRaftRight.to_u32(),
-                2 => GameInput::MoveLeftRaftLeft.to_u32(),
-                3 => GameInput::DeprecatedShootRightRaft.to_u32(),
-                4 => GameInput::MoveRightRaftRight.to_u32(),
-                5 => GameInput::MoveRightRaftLeft.to_u32(),
-                6 => GameInput::DeprecatedAddProjectile.to_u32(),
+            let (player, action) = match i % 33 {
+                0 => (PlayerId(0), Action::Fire),
+                1 => (PlayerId(0), Action::MoveRight),
+                2 => (PlayerId(0), Action::MoveLeft),
+                3 => (PlayerId(1), Action::Fire),
+                4 => (PlayerId(1), Action::MoveRight),
+                5 => (PlayerId(1), Action::MoveLeft),
+                6 => (PlayerId(1), Action::SelectNextFighter),
                 // repeat values so they occur more often
-                7 => GameInput::DeprecatedAddProjectile.to_u32(),
-                8 => GameInput::MoveLeftRaftRight.to_u32(),
-                _ => GameInput::NoOp.to_u32(),
-            });
+                7 => (PlayerId(1), Action::Fire),
+                8 => (PlayerId(0), Action::MoveRight),
+                9 => (PlayerId(0), Action::Fire),
+                _ => (PlayerId(0), Action::NoOp),
+            };
+            for other in [PlayerId(0), PlayerId(1)] {
+                let code = if other == player {
+                    action.to_u32()
+                } else {
+                    Action::NoOp.to_u32()
+                };
+                inputs[other.index()].push(code);
+            }
         }
 
-        state.tick(ticks, &inputs).unwrap();
+        inputs
+    }
+
+    #[test]
+    fn it_simulates_game_state() {
+        let mut state = GameState::new();
+        let ticks = 10000;
+        let [left, right] = simulated_inputs(state.config.tick_inputs_needed(ticks).unwrap());
+
+        state
+            .tick_with_encoding(ticks, &[&left, &right], InputEncoding::FixedChunks)
+            .unwrap();
 
-        assert_eq!(state.raft_left.entity.position.x, 7497);
+        // the left raft presses against the border of its side and loses the shootout
+        assert_eq!(state.outcome(), MatchOutcome::Won { team: 1 });
+        assert_eq!(state.ticks, 8590);
+        assert_eq!(state.rafts[raft(0)].curr_health, 0);
+        assert_eq!(state.rafts[raft(1)].curr_health, 1378);
+
+        let ebb = Fixed::from_uint(state.environment.tide_level(0) - state.environment.tide_level(state.ticks));
         assert_eq!(
-            state.raft_left.entity.position.y,
-            consts::LEFT_RAFT_INIT_POS.y
+            state.rafts[raft(0)].entity.position.x,
+            Fixed::from_uint(state.config.left_raft_max_x)
         );
-        assert_eq!(state.raft_right.entity.position.x, 5);
         assert_eq!(
-            state.raft_right.entity.position.y,
-            consts::RIGHT_RAFT_INIT_POS.y
+            state.rafts[raft(0)].entity.position.y,
+            consts::LEFT_RAFT_INIT_POS.y - ebb
         );
+        assert_eq!(state.rafts[raft(1)].entity.position.x.floor(), 7372);
+        assert_eq!(
+            state.rafts[raft(1)].entity.position.y,
+            consts::RIGHT_RAFT_INIT_POS.y - ebb
+        );
+    }
+
+    #[test]
+    fn it_accelerates_rafts_up_to_max_speed_and_drifts_to_a_stop() {
+        let mut state = GameState::new();
+        let max_speed = state.config.raft_max_speed;
+        let paddle = window(&[Action::MoveRight]);
+        let idle = window(&[]);
+
+        let mut top_speed = Fixed::ZERO;
+        for _ in 0..20 {
+            state.tick(state.config.ticks_per_input, &[&paddle, &idle]).unwrap();
+            let vx = state.rafts[raft(0)].entity.velocity.vx;
+            assert!(vx <= Fixed::from_int(max_speed));
+            top_speed = top_speed.max(vx);
+        }
+        assert!(top_speed > Fixed::from_int(state.config.velocity_gain_normal));
+
+        let mut prev_vx = state.rafts[raft(0)].entity.velocity.vx;
+        while prev_vx > Fixed::ZERO {
+            state.tick(1, &[&idle, &idle]).unwrap();
+            let vx = state.rafts[raft(0)].entity.velocity.vx;
+            assert!(vx < prev_vx);
+            prev_vx = vx;
+        }
+        assert_eq!(prev_vx, Fixed::ZERO);
+    }
+
+    #[test]
+    fn it_accelerates_heavier_rafts_less() {
+        let mut state = GameState::new();
+        state.rafts[raft(1)].mass = 2 * state.rafts[raft(0)].mass;
+        state.config.raft_drag = 0;
+
+        let paddle = window(&[Action::MoveUp]);
+        state.tick(1, &[&paddle, &paddle]).unwrap();
+        let gain = Fixed::from_int(state.config.velocity_gain_normal);
+        assert_eq!(state.rafts[raft(0)].entity.velocity.vy, gain);
+        assert_eq!(state.rafts[raft(1)].entity.velocity.vy, gain.mul_div(1, 2));
+    }
+
+    #[test]
+    fn it_charges_past_max_speed() {
+        let mut state = GameState::new();
+        let charge = window(&[Action::Charge, Action::MoveRight]);
+        state.tick(1, &[&charge, &window(&[])]).unwrap();
+
+        // paddling on top of a charge neither adds to it nor brakes it
+        let vx = state.rafts[raft(0)].entity.velocity.vx;
+        assert!(vx > Fixed::from_int(state.config.raft_max_speed));
+        assert!(vx <= Fixed::from_int(state.config.velocity_gain_boost));
     }
 
     #[test]
     fn it_enforces_world_bounds() {
         let mut state = GameState::new();
         let ticks = 10000;
-        let inputs_needed = tick_inputs_needed(ticks);
-        let inputs = vec![GameInput::MoveLeftRaftRight.to_u32(); inputs_needed as usize];
+        let windows = state.config.input_windows_needed(ticks);
+        let inputs = repeat_window(&[Action::MoveRight], windows);
+        let idle = repeat_window(&[], windows);
         // TODO: test projectiles respect world bounds and are correctly removed. also add in raft_right
-        state.tick(ticks, &inputs).unwrap();
+        state.tick(ticks, &[&inputs, &idle]).unwrap();
+
+        let left = &state.rafts[raft(0)];
+        assert!(left.entity.position.x + Fixed::from_uint(left.width) < Fixed::from_uint(consts::WORLD_MAX_X));
+    }
+
+    #[test]
+    fn it_keeps_each_raft_on_its_own_side() {
+        let mut state = GameState::new();
+        let ticks = 2000;
+        let windows = state.config.input_windows_needed(ticks);
+        let forward = repeat_window(&[Action::MoveRight], windows);
+        let backward = repeat_window(&[Action::MoveLeft], windows);
+        state.tick(ticks, &[&forward, &backward]).unwrap();
+
+        let (left, right) = (&state.rafts[raft(0)], &state.rafts[raft(1)]);
+        assert_eq!(left.entity.position.x, Fixed::from_uint(state.config.left_raft_max_x));
+        assert_eq!(right.entity.position.x, Fixed::from_uint(state.config.right_raft_min_x));
+        assert!(left.entity.position.x + Fixed::from_uint(left.width) <= right.entity.position.x);
+
+        // fighters stay on board when their raft hits the border
+        for raft in state.rafts.values() {
+            for &id in &raft.crew {
+                assert!(state.fighters[id].collides_with(raft));
+            }
+        }
+    }
+
+    #[test]
+    fn it_stops_rafts_running_into_each_other() {
+        let mut state = GameState::new();
+        for raft in state.rafts.values_mut() {
+            raft.zone = state.config.zone_for_team(2);
+        }
+        let (left, right) = (&state.rafts[raft(0)], &state.rafts[raft(1)]);
+        let gap = right.entity.position.x - (left.entity.position.x + Fixed::from_uint(left.width));
+        state.rafts[raft(1)].move_by(&mut state.fighters, Fixed::from_int(10) - gap, Fixed::ZERO);
+        state.rafts[raft(0)].entity.velocity.vx = Fixed::from_int(30);
+
+        let idle = window(&[]);
+        state.tick(1, &[&idle, &idle]).unwrap();
+
+        let (left, right) = (&state.rafts[raft(0)], &state.rafts[raft(1)]);
+        assert!(!left.collides_with(right));
+        assert_eq!(left.entity.velocity.vx, right.entity.velocity.vx);
+        assert!(left.entity.velocity.vx > Fixed::ZERO);
+
+        // both sides take the same damage from a head on collision between equal rafts
+        assert!(left.curr_health < left.max_health);
+        assert_eq!(left.curr_health, right.curr_health);
+    }
+
+    #[test]
+    fn it_rams_across_the_border_and_paddles_back() {
+        let mut state = GameState::new();
+        let border = Fixed::from_uint(state.config.left_raft_max_x);
+        let x = state.rafts[raft(0)].entity.position.x;
+        state.rafts[raft(0)].move_by(&mut state.fighters, border - x, Fixed::ZERO);
+        let x = state.rafts[raft(1)].entity.position.x;
+        let border_right = Fixed::from_uint(state.config.right_raft_min_x);
+        state.rafts[raft(1)].move_by(&mut state.fighters, border_right - x, Fixed::ZERO);
+
+        let idle = window(&[]);
+        state.tick(1, &[&window(&[Action::Ram]), &idle]).unwrap();
+
+        // a plain charge at full speed couldn't do that much damage
+        let right = &state.rafts[raft(1)];
+        let charge_damage = state.config.velocity_gain_boost.unsigned_abs() * state.config.ram_damage;
+        assert!(right.max_health - right.curr_health > charge_damage);
+
+        let ticks = state.config.ram_duration + 100;
+        let idle = repeat_window(&[], state.config.input_windows_needed(ticks));
+        state.tick(ticks, &[&idle, &idle]).unwrap();
+        assert_eq!(state.rafts[raft(0)].ram_ticks, 0);
+        assert!(state.rafts[raft(0)].entity.position.x <= border);
+    }
+
+    #[test]
+    fn it_reports_match_outcome() {
+        let mut state = GameState::new();
+        assert_eq!(state.outcome(), MatchOutcome::InProgress);
+
+        state.rafts[raft(1)].curr_health = 0;
+        state.rafts[raft(1)].entity.is_active = false;
+        assert_eq!(state.outcome(), MatchOutcome::Won { team: 0 });
+
+        state.rafts[raft(0)].curr_health = 0;
+        state.rafts[raft(0)].entity.is_active = false;
+        assert_eq!(state.outcome(), MatchOutcome::Draw);
+
+        state.rafts[raft(1)].curr_health = consts::DEFAULT_RAFT_HEALTH;
+        state.rafts[raft(1)].entity.is_active = true;
+        assert_eq!(state.outcome(), MatchOutcome::Won { team: 1 });
+    }
+
+    #[test]
+    fn it_keeps_the_outcome_codes_of_two_team_matches() {
+        let codes = [
+            MatchOutcome::InProgress,
+            MatchOutcome::Won { team: 0 },
+            MatchOutcome::Won { team: 1 },
+            MatchOutcome::Draw,
+            MatchOutcome::Won { team: 2 },
+            MatchOutcome::Won { team: 3 },
+        ]
+        .map(|outcome| outcome.to_u32());
+        assert_eq!(codes, [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn it_stops_ticking_once_match_is_finished() {
+        let mut state = GameState::new();
+        // leave the right raft one hit away from sinking
+        state.rafts[raft(1)].curr_health = 1;
+
+        let ticks = 10000;
+        let [left, right] = simulated_inputs(state.config.tick_inputs_needed(ticks).unwrap());
+        let inputs: [&[u32]; 2] = [&left, &right];
+        state
+            .tick_with_encoding(ticks, &inputs, InputEncoding::FixedChunks)
+            .unwrap();
+
+        assert_eq!(state.outcome(), MatchOutcome::Won { team: 0 });
+        assert!(state.ticks < ticks);
+
+        let finished_at = state.ticks;
+        let snapshot = state.to_serialized_state().unwrap();
+
+        let err = state
+            .tick_with_encoding(ticks, &inputs, InputEncoding::FixedChunks)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SimulationError::MatchFinished {
+                num_tick,
+                outcome: MatchOutcome::Won { team: 0 },
+            } if num_tick == finished_at
+        ));
+        assert_eq!(state.to_serialized_state().unwrap(), snapshot);
+    }
+
+    #[test]
+    fn it_fires_only_on_command() {
+        let mut state = GameState::new();
+
+        let idle = window(&[]);
+        state
+            .tick(state.config.ticks_per_input, &[&idle, &idle])
+            .unwrap();
+        assert!(state.projectiles.is_empty());
+
+        let fire = window(&[Action::Fire]);
+        state
+            .tick(state.config.ticks_per_input, &[&fire, &idle])
+            .unwrap();
+        assert_eq!(state.projectile_count(0), 1);
+        assert_eq!(state.projectile_count(1), 0);
+    }
+
+    #[test]
+    fn it_aims_and_fires_the_selected_fighter() {
+        let mut state = GameState::new();
+
+        let inputs = window(&[
+            Action::SelectNextFighter,
+            Action::AimClockwise,
+            Action::Fire,
+        ]);
+        state.tick(1, &[&window(&[]), &inputs]).unwrap();
+
+        assert_eq!(state.rafts[raft(1)].selected_fighter, 1);
+        let west = Bearings::West.angle();
+        let step = Angle::from_steps(i64::from(state.config.aim_step));
+        assert_eq!(state.fighters[state.rafts[raft(1)].crew[0]].aim, west);
+        assert_eq!(state.fighters[state.rafts[raft(1)].crew[1]].aim, west - step);
+
+        assert_eq!(state.projectile_count(1), 1);
+        let projectile = state.projectiles.values().next().unwrap();
+        assert!(projectile.entity.velocity.vx.is_negative());
+        assert!(projectile.entity.velocity.vy > Fixed::ZERO);
+    }
+
+    #[test]
+    fn it_keeps_the_selection_when_fighters_are_knocked_out() {
+        // the right raft takes on a third fighter carrying a flamethrower
+        let fire_after_knock_outs = |selected, knocked_out: &[usize]| {
+            let mut state = GameState::new();
+            let mut third = state.fighters[state.rafts[raft(1)].crew[1]].clone();
+            third.gun = GunTypes::FlameThrower;
+            third.weapon = WeaponState::new(&third.gun);
+            state.board(raft(1), vec![third]);
+            let crew = state.rafts[raft(1)].crew.clone();
+            assert_eq!(crew.len(), 3);
+
+            state.rafts[raft(1)].selected_fighter = selected;
+            for &i in knocked_out {
+                state.fighters[crew[i]].curr_health = 0;
+                state.fighters[crew[i]].entity.is_active = false;
+            }
+            // knocked out fighters leave the raft during the first window, the next one fires
+            let idle = window(&[]);
+            state.tick(state.config.ticks_per_input, &[&idle, &idle]).unwrap();
+            state.tick(1, &[&idle, &window(&[Action::Fire])]).unwrap();
+
+            let shots = state.projectiles.values().filter(|p| p.team == 1);
+            let guns: Vec<_> = shots.map(|p| p.gun).collect();
+            (state.rafts[raft(1)].selected_fighter, guns)
+        };
+
+        // the selected fighter stays selected when one before it goes down
+        let (selected, guns) = fire_after_knock_outs(2, &[0]);
+        assert_eq!(selected, 1);
+        assert!(!guns.is_empty() && guns.iter().all(|&gun| gun == GunTypes::FlameThrower));
+
+        // with the selected fighter gone too, the one after it takes over
+        let (selected, guns) = fire_after_knock_outs(1, &[0, 1]);
+        assert_eq!(selected, 0);
+        assert!(!guns.is_empty() && guns.iter().all(|&gun| gun == GunTypes::FlameThrower));
+
+        // the last fighter going down wraps the selection around
+        let (selected, guns) = fire_after_knock_outs(2, &[2]);
+        assert_eq!(selected, 0);
+        assert_eq!(guns, vec![GunTypes::Bazooka]);
+    }
+
+    #[test]
+    fn it_aims_at_the_closest_enemy() {
+        let mut state = GameState::new();
+
+        let inputs = window(&[Action::AimClockwise, Action::AimClockwise, Action::AimAtEnemy]);
+        state.tick(1, &[&window(&[]), &inputs]).unwrap();
+
+        let fighter = &state.fighters[state.rafts[raft(1)].crew[0]];
+        let enemy = state.rafts[raft(0)].center();
+        assert_eq!(fighter.aim, Angle::towards(&fighter.center(), &enemy));
+    }
+
+    #[test]
+    fn it_picks_the_launch_profile_from_the_aim() {
+        let cases = [
+            (Bearings::East, ProjectileDirection::ArchRight),
+            (Bearings::Northeast, ProjectileDirection::ArchRight),
+            (Bearings::Southwest, ProjectileDirection::ArchLeft),
+            (Bearings::South, ProjectileDirection::StraightDown),
+            (Bearings::North, ProjectileDirection::StraightDown),
+        ];
+        for (aim, expected) in cases {
+            assert_eq!(ProjectileDirection::from_aim(aim.angle()), expected);
+        }
+
+        let spec = GunTypes::SMG.spec();
+        let flat = ProjectileDirection::ArchLeft.launch_velocity(Bearings::West.angle(), spec);
+        let (speed, lift) = (Fixed::from_int(spec.speed), Fixed::from_int(spec.lift));
+        assert_eq!((flat.vx, flat.vy), (-speed, lift));
+        let steep = ProjectileDirection::ArchRight.launch_velocity(Bearings::Northeast.angle(), spec);
+        assert_eq!((steep.vx, steep.vy), (speed, lift + speed));
+        let drop = ProjectileDirection::StraightDown.launch_velocity(Bearings::South.angle(), spec);
+        assert_eq!((drop.vx, drop.vy), (Fixed::ZERO, -speed));
+
+        // halfway between east and northeast the arc steepens by half as much
+        let between = Angle::from_steps(64);
+        assert_eq!(ProjectileDirection::from_aim(between), ProjectileDirection::ArchRight);
+        let shallow = ProjectileDirection::ArchRight.launch_velocity(between, spec);
+        assert_eq!(shallow.vx, speed);
+        assert!(shallow.vy > lift && shallow.vy < lift + speed.mul_div(1, 2));
+    }
+
+    #[test]
+    fn it_arcs_shots_over_the_water() {
+        let mut state = GameState::new();
+        let gravity = Fixed::from_int(state.config.gravity);
+        let idle = window(&[]);
+        state.tick(1, &[&window(&[Action::Fire]), &idle]).unwrap();
+
+        let (id, shot) = state.projectiles.iter().next().unwrap();
+        let launch_y = shot.entity.position.y;
+        let mut apex = launch_y;
+        while let Some(shot) = state.projectiles.get(id).cloned() {
+            state.tick(1, &[&idle, &idle]).unwrap();
+            if let Some(next) = state.projectiles.get(id) {
+                assert_eq!(next.entity.velocity.vy, shot.entity.velocity.vy - gravity);
+                apex = apex.max(next.entity.position.y);
+            }
+        }
+
+        assert!(apex > launch_y);
+        let right = &state.rafts[raft(1)];
+        let fighter_hit = crew(&state, 1).iter().any(|f| f.curr_health < f.max_health);
+        assert!(right.curr_health < right.max_health || fighter_hit);
+    }
+
+    #[test]
+    fn it_only_pulls_ballistic_projectiles_down() {
+        let mut state = GameState::new();
+        let position = Position {
+            x: Fixed::from_uint(5000),
+            y: Fixed::from_uint(5000),
+        };
+        let [ballistic, straight] = [GunTypes::SMG, GunTypes::StraightShooter].map(|gun| {
+            let mut shot = projectile_at(gun, 0, position.clone(), &state.config);
+            shot.entity.velocity.vx = Fixed::ONE;
+            state.projectiles.spawn(shot)
+        });
+
+        let idle = window(&[]);
+        state.tick(1, &[&idle, &idle]).unwrap();
+        assert_eq!(state.projectiles[ballistic].entity.velocity.vy, -Fixed::from_int(state.config.gravity));
+        assert_eq!(state.projectiles[straight].entity.velocity.vy, Fixed::ZERO);
+    }
+
+    #[test]
+    fn it_carries_projectiles_with_the_wind() {
+        let mut state = GameState::new();
+        state.environment.wind = Velocity {
+            vx: Fixed::from_int(3),
+            vy: Fixed::from_int(-2),
+        };
+        let position = Position {
+            x: Fixed::from_uint(5000),
+            y: Fixed::from_uint(5000),
+        };
+        let shot = projectile_at(GunTypes::StraightShooter, 0, position, &state.config);
+        let id = state.projectiles.spawn(shot);
+
+        let idle = window(&[]);
+        state.tick(2, &[&idle, &idle]).unwrap();
+        let drifted = &state.projectiles[id].entity.position;
+        assert_eq!((drifted.x, drifted.y), (Fixed::from_uint(5006), Fixed::from_uint(4996)));
+    }
+
+    #[test]
+    fn it_respects_weapon_cooldown_and_reload() {
+        let mut state = GameState::new();
+        let config = &state.config;
+        let fighter = &mut state.fighters[state.rafts[raft(0)].crew[0]];
+        let spec = fighter.gun.spec();
+        let magazine_size = spec.magazine_size;
+        let fire_rate = spec.fire_rate;
+        let reload_time = spec.reload_time;
+
+        assert_eq!(fighter.fire(0, config).len(), 1);
+        // still cooling down
+        assert!(fighter.fire(0, config).is_empty());
+        assert_eq!(fighter.weapon.magazine, magazine_size - 1);
+
+        for _ in 1..magazine_size {
+            for _ in 0..fire_rate {
+                fighter.update_weapon();
+            }
+            assert_eq!(fighter.fire(0, config).len(), 1);
+        }
+        assert_eq!(fighter.weapon.magazine, 0);
+        assert_eq!(fighter.weapon.reload, reload_time);
+
+        for _ in 0..reload_time - 1 {
+            fighter.update_weapon();
+        }
+        assert!(fighter.fire(0, config).is_empty());
+
+        fighter.update_weapon();
+        assert_eq!(fighter.weapon.magazine, magazine_size);
+        assert_eq!(fighter.fire(0, config).len(), 1);
+    }
+
+    #[test]
+    fn it_staggers_fighters_by_their_own_cooldown() {
+        let mut state = GameState::new();
+        let ticks = state.config.ticks_per_input;
+        let idle = window(&[]);
+
+        state
+            .tick(ticks, &[&idle, &window(&[Action::Fire])])
+            .unwrap();
+
+        // the bazooka is still cooling down but the second fighter is ready
+        let inputs = window(&[Action::SelectNextFighter, Action::Fire]);
+        state.tick(ticks, &[&idle, &inputs]).unwrap();
+
+        assert_eq!(state.projectile_count(1), 2);
+        let fighters = crew(&state, 1);
+        assert!(fighters[0].weapon.cooldown > 0);
+        assert!(fighters[1].weapon.cooldown > 0);
+        assert_ne!(fighters[0].weapon.cooldown, fighters[1].weapon.cooldown);
+    }
+
+    #[test]
+    fn it_simulates_with_a_custom_config() {
+        let config = GameConfig {
+            ticks_per_input: 2,
+            tick_input_api_chunk_size: 3,
+            ..GameConfig::default()
+        };
+        let mut state = GameState::with_config(config).unwrap();
+        // the config is part of the serialized (and therefore hashed) state
+        assert_ne!(
+            state.to_serialized_state().unwrap(),
+            GameState::new().to_serialized_state().unwrap()
+        );
+
+        // 5 ticks at 2 ticks per input need 3 windows of at most 3 inputs each
+        let inputs = vec![Action::MoveRight.to_u32(); 9];
+        state
+            .tick_with_encoding(5, &[&inputs, &inputs], InputEncoding::FixedChunks)
+            .unwrap();
+        let inputs = repeat_window(&[Action::MoveRight, Action::MoveUp, Action::Fire], 3);
+        state.tick(5, &[&inputs, &inputs]).unwrap();
+        assert_eq!(state.ticks, 10);
+
+        let invalid = GameConfig {
+            raft_height: 0,
+            ..GameConfig::default()
+        };
+        assert!(matches!(
+            GameState::with_config(invalid),
+            Err(SimulationError::InvalidConfig { field: "raft_height" })
+        ));
+    }
+
+    #[test]
+    fn it_decodes_both_encodings_identically() {
+        let ticks = 2000;
+        let mut legacy_state = GameState::new();
+        let [legacy_left, legacy_right] =
+            simulated_inputs(legacy_state.config.tick_inputs_needed(ticks).unwrap());
+
+        // drop the NoOp padding, it is implied by the window length
+        let chunk_size = legacy_state.config.tick_input_api_chunk_size;
+        let reencode = |legacy: &[u32]| {
+            let noop = Action::NoOp.to_u32();
+            let windows = decode_fixed_chunks(legacy, chunk_size)
+                .into_iter()
+                .map(|w| w.iter().copied().filter(|&i| i != noop).collect::<Vec<u32>>())
+                .collect::<Vec<Vec<u32>>>();
+            encode_length_prefixed(&windows)
+        };
+        let left = reencode(&legacy_left);
+        let right = reencode(&legacy_right);
+        assert!(left.len() < legacy_left.len());
+
+        let mut state = GameState::new();
+        legacy_state
+            .tick_with_encoding(ticks, &[&legacy_left, &legacy_right], InputEncoding::FixedChunks)
+            .unwrap();
+        state.tick(ticks, &[&left, &right]).unwrap();
+
+        assert_eq!(state.to_serialized_state().unwrap(), legacy_state.to_serialized_state().unwrap());
+    }
+
+    #[test]
+    fn it_rejects_malformed_input_streams() {
+        let fire = Action::Fire.to_u32();
+
+        assert!(matches!(
+            decode_length_prefixed(&[1, fire, 0], 3, 10),
+            Err(SimulationError::MissingInputWindow {
+                window: 2,
+                expected: 3
+            })
+        ));
+        assert!(matches!(
+            decode_length_prefixed(&[11], 1, 10),
+            Err(SimulationError::InputWindowTooLong {
+                window: 0,
+                received: 11,
+                max: 10
+            })
+        ));
+        assert!(matches!(
+            decode_length_prefixed(&[0, 3, fire], 2, 10),
+            Err(SimulationError::TruncatedInputWindow {
+                window: 1,
+                declared: 3,
+                available: 1
+            })
+        ));
+        assert!(matches!(
+            decode_length_prefixed(&[0, 0, fire], 2, 10),
+            Err(SimulationError::TrailingInput { received: 1 })
+        ));
+
+        let input = [2, fire, fire, 0];
+        let windows = decode_length_prefixed(&input, 2, 10).unwrap();
+        assert_eq!(windows, vec![&[fire, fire][..], &[][..]]);
+    }
+
+    #[test]
+    fn it_applies_player_inputs_to_their_own_raft_only() {
+        let mut state = GameState::new();
+        let initial_state = state.clone();
+        let ticks = state.config.ticks_per_input;
+
+        let inputs = window(&[Action::MoveLeft, Action::Fire]);
+        state.tick(ticks, &[&inputs, &window(&[])]).unwrap();
+
+        assert!(state.rafts[raft(0)].entity.position.x < initial_state.rafts[raft(0)].entity.position.x);
+        assert_eq!(
+            state.rafts[raft(1)].entity.position.x,
+            initial_state.rafts[raft(1)].entity.position.x
+        );
+        assert_eq!(state.projectile_count(0), 1);
+        assert_eq!(state.projectile_count(1), 0);
+
+        // charging always heads towards the opponent
+        let charge = window(&[Action::Charge]);
+        state.tick(ticks, &[&charge, &charge]).unwrap();
+        assert!(state.rafts[raft(0)].entity.velocity.vx > Fixed::ZERO);
+        assert!(state.rafts[raft(1)].entity.velocity.vx.is_negative());
+    }
+
+    #[test]
+    fn it_requires_one_input_stream_per_player() {
+        let mut state = GameState::new();
+        let inputs = window(&[Action::Fire]);
+
+        assert!(matches!(
+            state.tick(state.config.ticks_per_input, &[&inputs]),
+            Err(SimulationError::InvalidPlayerCount {
+                received: 1,
+                expected: 2
+            })
+        ));
+        assert_eq!(state.ticks, 0);
+    }
+
+    fn duelling_raft(state: &GameState, player: u32, team: u32) -> (Raft, Vec<RaftFighter>) {
+        let mut hull = state.rafts[raft(player)].clone();
+        hull.team = team;
+        hull.crew.clear();
+        (hull, crew(state, player).into_iter().cloned().collect())
+    }
+
+    #[test]
+    fn it_resolves_hits_against_every_opposing_team() {
+        let duel = GameState::new();
+        // an ally of team 0 is moored right on top of the left raft, rafts can't overlap
+        let (mut ally, mut ally_crew) = duelling_raft(&duel, 0, 0);
+        ally.entity.position.y += Fixed::from_uint(ally.height);
+        for fighter in &mut ally_crew {
+            fighter.entity.position.y += Fixed::from_uint(ally.height);
+        }
+        let rafts = vec![
+            duelling_raft(&duel, 0, 0),
+            duelling_raft(&duel, 1, 1),
+            (ally, ally_crew),
+        ];
+        let mut state = GameState::with_rafts(duel.config, rafts).unwrap();
+
+        // right on the seam between both rafts
+        let left = &state.rafts[raft(0)];
+        let seam = Position {
+            x: left.entity.position.x + Fixed::from_uint(10),
+            y: left.entity.position.y + Fixed::from_uint(left.height),
+        };
+        let radius = state.config.projectile_radius;
+        let shell = move |team, gun| {
+            Projectile::new(
+                Entity {
+                    position: seam.clone(),
+                    velocity: consts::NO_VELOCITY,
+                    is_active: true,
+                },
+                radius,
+                gun,
+                team,
+            )
+        };
+        // friendly fire is ignored, the hostile shell only hits its first contact
+        state.projectiles = [shell(0, GunTypes::SMG), shell(1, GunTypes::SMG)]
+            .into_iter()
+            .collect();
+
+        let idle = window(&[]);
+        state.tick(1, &[&idle, &idle, &idle]).unwrap();
+
+        assert_eq!(state.projectile_count(0), 1);
+        assert_eq!(state.projectile_count(1), 0);
+        let health = |state: &GameState| [state.rafts[raft(0)].curr_health, state.rafts[raft(2)].curr_health];
+        let before = health(&state);
+        let damaged = before.iter().filter(|&&h| h < consts::DEFAULT_RAFT_HEALTH).count();
+        assert_eq!(damaged, 1);
+        assert_eq!(state.rafts[raft(1)].curr_health, state.rafts[raft(1)].max_health);
+
+        // a piercing shot goes on to hit both rafts
+        state.projectiles = core::iter::once(shell(1, GunTypes::StraightShooter)).collect();
+        state.tick(1, &[&idle, &idle, &idle]).unwrap();
+        let after = health(&state);
+        assert!(after[0] < before[0] && after[1] < before[1]);
+
+        // team 0 only loses once all its rafts are sunk
+        state.rafts[raft(0)].curr_health = 0;
+        assert_eq!(state.outcome(), MatchOutcome::InProgress);
+        state.rafts[raft(2)].curr_health = 0;
+        assert_eq!(state.outcome(), MatchOutcome::Won { team: 1 });
+    }
+
+    #[test]
+    fn it_hits_only_the_first_contact_and_reports_it() {
+        let mut state = GameState::new();
+        // the fighter stands on the raft, overlapping both it shields the hull
+        let fighter_id = state.rafts[raft(1)].crew[0];
+        let fighter = &state.fighters[fighter_id].entity.position;
+        let position = Position {
+            x: fighter.x + Fixed::from_uint(50),
+            y: fighter.y + Fixed::from_uint(50),
+        };
+        let shot = projectile_at(GunTypes::SMG, 0, position, &state.config);
+        state.projectiles.spawn(shot);
+
+        let idle = window(&[]);
+        state.tick(1, &[&idle, &idle]).unwrap();
+
+        let target = &state.rafts[raft(1)];
+        assert!(state.projectiles.is_empty());
+        assert_eq!(target.curr_health, target.max_health);
+        let fighter = &state.fighters[fighter_id];
+        assert_eq!(
+            state.events,
+            vec![GameEvent::Hit(HitEvent {
+                tick: 0,
+                team: 0,
+                gun: GunTypes::SMG,
+                target: HitTarget::Fighter {
+                    raft: raft(1),
+                    fighter: fighter_id,
+                },
+                damage: fighter.max_health - fighter.curr_health,
+                splash: false,
+            })]
+        );
+
+        // events only cover the latest tick call
+        state.tick(1, &[&idle, &idle]).unwrap();
+        assert!(state.events.is_empty());
+    }
+
+    #[test]
+    fn it_orders_contacts_by_when_each_target_was_entered() {
+        let state = GameState::new();
+        let grid = target_grid(&state.rafts, &state.fighters, state.config.grid_cell_size);
+        let fighter_id = state.rafts[raft(1)].crew[0];
+        // flying in from the left, the shot crosses the edge of the hull well before the
+        // fighter standing a fifth of the way in
+        let end = state.fighters[fighter_id].center();
+        let origin = Position {
+            x: state.rafts[raft(1)].entity.position.x - Fixed::from_uint(20),
+            y: end.y,
+        };
+        let shot = projectile_at(GunTypes::SMG, 0, end, &state.config);
+
+        let found = contacts(&shot, &origin, &state.rafts, &state.fighters, &grid);
+        assert!(found[0].0 < found[1].0);
+        assert_eq!(
+            found.into_iter().map(|(_, target)| target).collect::<Vec<_>>(),
+            vec![
+                HitTarget::Raft { raft: raft(1) },
+                HitTarget::Fighter {
+                    raft: raft(1),
+                    fighter: fighter_id,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_never_resolves_a_despawned_id() {
+        let state = GameState::new();
+        let shot = |x| {
+            let position = Position {
+                x: Fixed::from_uint(x),
+                y: Fixed::ZERO,
+            };
+            projectile_at(GunTypes::SMG, 0, position, &state.config)
+        };
+        let mut projectiles: Components<ProjectileId, Projectile> = Components::new();
+        let first = projectiles.spawn(shot(1));
+        let second = projectiles.spawn(shot(2));
+
+        assert_eq!(projectiles.despawn(first).unwrap().entity.position.x, Fixed::from_uint(1));
+        assert!(projectiles.get(first).is_none());
+        assert!(projectiles.despawn(first).is_none());
+
+        // the freed slot is reused under a new generation
+        let third = projectiles.spawn(shot(3));
+        assert_eq!(third.index(), first.index());
+        assert_ne!(third, first);
+        assert!(projectiles.get(first).is_none());
+        assert_eq!(projectiles[third].entity.position.x, Fixed::from_uint(3));
+        assert_eq!(projectiles[second].entity.position.x, Fixed::from_uint(2));
+        assert_eq!(projectiles.ids().collect::<Vec<_>>(), vec![third, second]);
+    }
+
+    #[test]
+    fn it_finds_the_same_contacts_as_brute_force() {
+        let state = GameState::new();
+        let config = &state.config;
+        let grid = target_grid(&state.rafts, &state.fighters, state.config.grid_cell_size);
+        // a single cell holding everything checks every pair
+        let everything = target_grid(&state.rafts, &state.fighters, u32::MAX);
+
+        let mut rng = Rng::new(42);
+        let mut hits = 0;
+        for shot in 0..4000 {
+            let gun = if shot % 2 == 0 { GunTypes::SMG } else { GunTypes::Bazooka };
+            let position = Position {
+                x: Fixed::from_uint(rng.below(config.world_max_x)),
+                y: Fixed::from_uint(rng.below(config.world_max_y)),
+            };
+            let mut projectile = projectile_at(gun, rng.below(2), position.clone(), config);
+            let origin = Position {
+                x: position.x + Fixed::from_int(rng.range_i32(-400, 400)),
+                y: position.y + Fixed::from_int(rng.range_i32(-400, 400)),
+            };
+            projectile.entity.position = position;
+
+            let found = contacts(&projectile, &origin, &state.rafts, &state.fighters, &grid);
+            let brute_force = contacts(&projectile, &origin, &state.rafts, &state.fighters, &everything);
+            assert_eq!(found, brute_force);
+            hits += usize::from(!found.is_empty());
+        }
+        assert!(hits > 0);
+    }
+
+    #[test]
+    fn it_requires_at_least_two_teams() {
+        let duel = GameState::new();
+        let rafts = vec![duelling_raft(&duel, 0, 3), duelling_raft(&duel, 1, 3)];
+
+        assert!(matches!(
+            GameState::with_rafts(duel.config, rafts),
+            Err(SimulationError::NotEnoughTeams {})
+        ));
+    }
+
+    #[test]
+    fn it_serializes_the_rng_state() {
+        let mut state = GameState::new();
+        let initial = state.to_serialized_state().unwrap();
+
+        // drawing a number is a state change both players have to agree on
+        state.rng.next_u32();
+        let advanced = state.to_serialized_state().unwrap();
+        assert_ne!(initial, advanced);
+
+        let restored = GameState::from_serialized_state(&advanced).unwrap();
+        assert_eq!(restored.rng, state.rng);
+
+        let reseeded = GameState::with_config(GameConfig {
+            rng_seed: 1,
+            ..GameConfig::default()
+        })
+        .unwrap();
+        assert_ne!(reseeded.to_serialized_state().unwrap(), initial);
+    }
+
+    #[test]
+    fn it_reports_where_a_state_fails_to_decode() {
+        // a CBOR string where the state map should start
+        let wrong_type = minicbor::to_vec("paddle").unwrap();
+        assert!(matches!(
+            GameState::from_serialized_state(&wrong_type),
+            Err(SimulationError::StateDecode { position: Some(0), .. })
+        ));
+
+        // running out of bytes has no position to point at
+        let encoded = GameState::new().to_serialized_state().unwrap();
+        let truncated = &encoded[..encoded.len() / 2];
+        assert!(matches!(
+            GameState::from_serialized_state(truncated),
+            Err(SimulationError::StateDecode { position: None, .. })
+        ));
+    }
+
+    fn projectile_at(gun: GunTypes, team: u32, position: Position, config: &GameConfig) -> Projectile {
+        let radius = config.projectile_radius * gun.spec().radius_multiplier;
+        Projectile::new(
+            Entity {
+                position,
+                velocity: consts::NO_VELOCITY,
+                is_active: true,
+            },
+            radius,
+            gun,
+            team,
+        )
+    }
+
+    #[test]
+    fn it_fires_flames_in_a_short_cone_with_falloff() {
+        let config = GameConfig::default();
+        let mut fighter = crew(&GameState::new(), 0)[0].clone();
+        fighter.gun = GunTypes::FlameThrower;
+        fighter.weapon = WeaponState::new(&fighter.gun);
+
+        let flames = fighter.fire(0, &config);
+        let spec = GunTypes::FlameThrower.spec();
+        assert_eq!(flames.len(), spec.pellets as usize);
+        let (speed, lift) = (Fixed::from_int(spec.speed), Fixed::from_int(spec.lift));
+        assert!(flames.iter().all(|f| f.entity.velocity.vx == speed));
+        // the cone is centred on the arc of a single shot
+        assert_eq!(flames[1].entity.velocity.vy, lift);
+        assert_eq!(flames[0].entity.velocity.vy + flames[2].entity.velocity.vy, lift + lift);
+        assert_ne!(flames[0].entity.velocity.vy, lift);
+
+        let mut flame = flames[1].clone();
+        assert_eq!(flame.damage(), spec.damage);
+        flame.age = spec.range / 2;
+        assert_eq!(flame.damage(), spec.damage / 2);
+        flame.age = spec.range;
+        assert_eq!(flame.damage(), 0);
+
+        // flames burn out once they reach their range
+        let mut state = GameState::new();
+        state.projectiles = flames.into_iter().collect();
+        let idle = repeat_window(&[], state.config.input_windows_needed(spec.range));
+        state.tick(spec.range, &[&idle, &idle]).unwrap();
+        assert!(state.projectiles.is_empty());
+    }
+
+    #[test]
+    fn it_pierces_each_raft_once_with_straight_shooters() {
+        let mut state = GameState::new();
+        let target = &state.rafts[raft(1)].entity.position;
+        let position = Position {
+            x: target.x + Fixed::from_uint(10),
+            y: target.y + Fixed::from_uint(10),
+        };
+        let shot = projectile_at(GunTypes::StraightShooter, 0, position, &state.config);
+        state.projectiles.spawn(shot);
+
+        let idle = window(&[]);
+        state.tick(1, &[&idle, &idle]).unwrap();
+        let health = state.rafts[raft(1)].curr_health;
+        assert!(health < state.rafts[raft(1)].max_health);
+        assert_eq!(state.projectiles.len(), 1);
+        assert_eq!(state.projectiles.values().next().unwrap().hit_rafts, vec![raft(1)]);
+
+        // still overlapping the raft but it was already pierced
+        state.tick(1, &[&idle, &idle]).unwrap();
+        assert_eq!(state.rafts[raft(1)].curr_health, health);
+    }
+
+    #[test]
+    fn it_splashes_bazooka_damage_around_the_impact() {
+        let mut state = GameState::new();
+        let target = &state.rafts[raft(1)];
+        // hit the raft below its first fighter, the second one is out of reach
+        let position = Position {
+            x: target.entity.position.x + Fixed::from_uint(target.width * 3 / 25),
+            y: target.entity.position.y + Fixed::from_uint(target.height / 2),
+        };
+        let shell = projectile_at(GunTypes::Bazooka, 0, position, &state.config);
+        state.projectiles.spawn(shell);
+
+        let idle = window(&[]);
+        state.tick(1, &[&idle, &idle]).unwrap();
+
+        let target = &state.rafts[raft(1)];
+        assert!(state.projectiles.is_empty());
+        // rafts don't resist explosives, only their armor rating soaks some of the hit
+        assert_eq!(
+            target.max_health - target.curr_health,
+            GunTypes::Bazooka.spec().damage - target.armor.rating
+        );
+        let fighters = crew(&state, 1);
+        assert_eq!(
+            fighters[0].max_health - fighters[0].curr_health,
+            GunTypes::Bazooka.spec().splash_damage
+        );
+        assert_eq!(fighters[1].curr_health, fighters[1].max_health);
+        let splashed: Vec<_> = state
+            .events
+            .iter()
+            .map(|GameEvent::Hit(hit)| (hit.target, hit.splash))
+            .collect();
+        assert_eq!(
+            splashed,
+            vec![
+                (HitTarget::Raft { raft: raft(1) }, false),
+                (
+                    HitTarget::Fighter {
+                        raft: raft(1),
+                        fighter: target.crew[0],
+                    },
+                    true
+                ),
+            ]
+        );
+
+        // the SMG trades damage per hit for fire rate
+        let smg = GunTypes::SMG.spec();
+        for gun in [GunTypes::Bazooka, GunTypes::FlameThrower, GunTypes::StraightShooter] {
+            assert!(gun.spec().damage > smg.damage);
+            assert!(gun.spec().fire_rate > smg.fire_rate);
+        }
+    }
+
+    #[test]
+    fn it_splashes_around_where_a_fast_shell_hit() {
+        let splash_at = |state: &mut GameState| {
+            let target = &state.rafts[raft(1)];
+            let y = state.fighters[target.crew[0]].center().y;
+            // in one tick the shell flew from short of the hull to past both fighters
+            let origin = Position {
+                x: target.entity.position.x - Fixed::from_uint(500),
+                y,
+            };
+            let end = Position {
+                x: target.entity.position.x + Fixed::from_uint(target.width * 22 / 25),
+                y,
+            };
+            let shell = projectile_at(GunTypes::Bazooka, 0, end, &state.config);
+            let id = state.projectiles.spawn(shell);
+            state.events.clear();
+            state.collide_projectiles(&[(id, origin)], 0);
+            state
+                .events
+                .iter()
+                .map(|GameEvent::Hit(hit)| (hit.target, hit.splash))
+                .collect::<Vec<_>>()
+        };
+
+        let mut state = GameState::new();
+        // a blast wide enough to reach the first fighter from the edge of the hull, but
+        // not the second one from where the shell ended up
+        state.config.projectile_radius = 150;
+        let first = state.rafts[raft(1)].crew[0];
+        assert_eq!(
+            splash_at(&mut state),
+            vec![
+                (HitTarget::Raft { raft: raft(1) }, false),
+                (
+                    HitTarget::Fighter {
+                        raft: raft(1),
+                        fighter: first,
+                    },
+                    true
+                ),
+            ]
+        );
 
-        assert!(state.raft_left.entity.position.x + state.raft_left.width < consts::WORLD_MAX_X);
+        // fighters that are out of the fight can't be caught in the blast
+        state.fighters[first].entity.is_active = false;
+        assert_eq!(splash_at(&mut state), vec![(HitTarget::Raft { raft: raft(1) }, false)]);
     }
 }
@@ -1,48 +1,162 @@
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
 use core::cmp::Ordering;
+use core::ops::RangeInclusive;
 
-use crate::world::{Projectile, Raft, RaftFighter};
+use crate::config::GameConfig;
+use crate::environment::Environment;
+use crate::fixed::Fixed;
+use crate::trig::Angle;
+use crate::world::{Components, FighterId, Position, Projectile, Raft, RaftFighter};
 
-pub trait Collision {
-    /// Checks if the current object collides with another object of the same trait.
-    fn collides_with<T: Collision>(&self, other: &T) -> bool;
+/// Fixed point scale of drag coefficients, a drag of `DRAG_ONE` stops a raft within a tick
+pub const DRAG_ONE: u32 = 1024;
 
-    /// Returns the bounding box of the object as (x, y, width, height).
-    fn bounding_box(&self) -> (u32, u32, u32, u32);
+/// Fixed point scale of sweep times, `SWEEP_ONE` is the end of a tick's move
+pub const SWEEP_ONE: u32 = 1 << 16;
+
+/// Steps the projectile wobble advances per tick, an eighth of a turn
+const WOBBLE_STEPS_PER_TICK: i64 = Angle::STEPS as i64 / 8;
+
+/// Exact outline of a hitbox, matching what the frontend draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Rect {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    Circle {
+        x: u32,
+        y: u32,
+        radius: u32,
+    },
 }
 
-impl Collision for Raft {
+impl Shape {
+    /// Narrow phase test, touching edges don't count as overlapping.
+    #[must_use]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        match (*self, *other) {
+            (
+                Self::Rect {
+                    x: x1,
+                    y: y1,
+                    width: w1,
+                    height: h1,
+                },
+                Self::Rect {
+                    x: x2,
+                    y: y2,
+                    width: w2,
+                    height: h2,
+                },
+            ) => x1 < x2 + w2 && x1 + w1 > x2 && y1 < y2 + h2 && y1 + h1 > y2,
+            (Self::Circle { x, y, radius }, shape) | (shape, Self::Circle { x, y, radius }) => {
+                shape.clearance(i128::from(x), i128::from(y), radius, 1) < 0
+            }
+        }
+    }
+
+    /// Squared distance between the shape and a circle of `radius` centred on (`x`, `y`),
+    /// less the square of `radius`. Negative when they overlap.
+    ///
+    /// The centre is given in `scale`ths of a unit and so is the result, which lets a sweep
+    /// place the circle between whole positions without rounding.
+    fn clearance(&self, x: i128, y: i128, radius: u32, scale: i128) -> i128 {
+        let (dx, dy, reach) = match *self {
+            Self::Rect {
+                x: left,
+                y: top,
+                width,
+                height,
+            } => (
+                axis_gap(x, left, width, scale),
+                axis_gap(y, top, height, scale),
+                i128::from(radius),
+            ),
+            Self::Circle {
+                x: cx,
+                y: cy,
+                radius: other,
+            } => (
+                x - i128::from(cx) * scale,
+                y - i128::from(cy) * scale,
+                i128::from(radius) + i128::from(other),
+            ),
+        };
+        dx * dx + dy * dy - reach * reach * scale * scale
+    }
+}
+
+/// Distance from `point` to the span at `start` of length `size`, 0 inside it.
+fn axis_gap(point: i128, start: u32, size: u32, scale: i128) -> i128 {
+    let start = i128::from(start) * scale;
+    let end = start + i128::from(size) * scale;
+    (start - point).max(point - end).max(0)
+}
+
+pub trait Collision {
+    /// Checks if the current object collides with another object of the same trait.
+    ///
+    /// The bounding boxes are compared first as a cheap broad phase, only boxes that
+    /// overlap go on to the exact test of their shapes.
     fn collides_with<T: Collision>(&self, other: &T) -> bool {
         let (x1, y1, w1, h1) = self.bounding_box();
         let (x2, y2, w2, h2) = other.bounding_box();
 
         // AABB collision detection
-        x1 < x2 + w2 && x1 + w1 > x2 && y1 < y2 + h2 && y1 + h1 > y2
+        x1 < x2 + w2
+            && x1 + w1 > x2
+            && y1 < y2 + h2
+            && y1 + h1 > y2
+            && self.shape().overlaps(&other.shape())
+    }
+
+    /// Returns the bounding box of the object as (x, y, width, height).
+    fn bounding_box(&self) -> (u32, u32, u32, u32);
+
+    /// Exact hitbox of the object, its bounding box unless the object is round.
+    fn shape(&self) -> Shape {
+        let (x, y, width, height) = self.bounding_box();
+        Shape::Rect {
+            x,
+            y,
+            width,
+            height,
+        }
     }
 
+    /// Middle of the bounding box, what aiming at the object aims for.
+    fn center(&self) -> Position {
+        let (x, y, width, height) = self.bounding_box();
+        Position {
+            x: Fixed::from_uint(x) + Fixed::from_uint(width).mul_div(1, 2),
+            y: Fixed::from_uint(y) + Fixed::from_uint(height).mul_div(1, 2),
+        }
+    }
+}
+
+impl Collision for Raft {
     fn bounding_box(&self) -> (u32, u32, u32, u32) {
         (
-            self.entity.position.x,
-            self.entity.position.y,
+            self.entity.position.x.to_u32(),
+            self.entity.position.y.to_u32(),
             self.width,
             self.height,
         )
     }
 }
 
-// TODO: DRY up raftfighter and raft code in collides with and
 impl Collision for RaftFighter {
-    fn collides_with<T: Collision>(&self, other: &T) -> bool {
-        let (x1, y1, w1, h1) = self.bounding_box();
-        let (x2, y2, w2, h2) = other.bounding_box();
-
-        // AABB collision detection
-        x1 < x2 + w2 && x1 + w1 > x2 && y1 < y2 + h2 && y1 + h1 > y2
-    }
-
     fn bounding_box(&self) -> (u32, u32, u32, u32) {
         (
-            self.entity.position.x,
-            self.entity.position.y,
+            self.entity.position.x.to_u32(),
+            self.entity.position.y.to_u32(),
             self.width,
             self.height,
         )
@@ -50,95 +164,466 @@ impl Collision for RaftFighter {
 }
 
 impl Collision for Projectile {
-    fn collides_with<T: Collision>(&self, other: &T) -> bool {
-        let (x1, y1, w1, h1) = self.bounding_box();
-        let (x2, y2, w2, h2) = other.bounding_box();
+    fn bounding_box(&self) -> (u32, u32, u32, u32) {
+        self.bounding_box_at(&self.entity.position)
+    }
 
-        // AABB collision detection, simpler but less accurate for circles
-        x1 < x2 + w2 && x1 + w1 > x2 && y1 < y2 + h2 && y1 + h1 > y2
-        // TODO: check actual boundaries after cheaper check above
+    fn shape(&self) -> Shape {
+        Shape::Circle {
+            x: self.entity.position.x.to_u32(),
+            y: self.entity.position.y.to_u32(),
+            radius: self.radius,
+        }
     }
+}
 
-    fn bounding_box(&self) -> (u32, u32, u32, u32) {
-        let diameter = 2 * self.radius;
-        // (x1, y1, w1, h1)
+/// Uniform grid broadphase, rebuilt every tick.
+///
+/// Objects are filed under every cell their bounding box touches, so querying an area
+/// returns every key whose box could overlap it. Cells live in a `BTreeMap` and results
+/// come back sorted, so lookups don't depend on insertion order.
+pub struct SpatialGrid<K> {
+    cell_size: u32,
+    cells: BTreeMap<(u32, u32), Vec<K>>,
+}
+
+impl<K: Copy + Ord> SpatialGrid<K> {
+    #[must_use]
+    pub fn new(cell_size: u32) -> Self {
+        Self {
+            cell_size: cell_size.max(1),
+            cells: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, key: K, bounding_box: (u32, u32, u32, u32)) {
+        let (columns, rows) = self.cells_covering(bounding_box);
+        for column in columns {
+            for row in rows.clone() {
+                self.cells.entry((column, row)).or_default().push(key);
+            }
+        }
+    }
+
+    /// Keys of everything filed in a cell that `area` touches, sorted and without duplicates.
+    #[must_use]
+    pub fn query(&self, area: (u32, u32, u32, u32)) -> Vec<K> {
+        let (columns, rows) = self.cells_covering(area);
+        let mut keys = vec![];
+        for column in columns {
+            let cells = self.cells.range((column, *rows.start())..=(column, *rows.end()));
+            for (_, cell) in cells {
+                keys.extend_from_slice(cell);
+            }
+        }
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    const fn cells_covering(
+        &self,
+        (x, y, width, height): (u32, u32, u32, u32),
+    ) -> (RangeInclusive<u32>, RangeInclusive<u32>) {
         (
-            self.entity.position.x.saturating_sub(self.radius),
-            self.entity.position.y.saturating_sub(self.radius),
-            // width smaller on purpose so projectile goes into other entity
-            self.radius,
-            diameter,
+            x / self.cell_size..=x.saturating_add(width) / self.cell_size,
+            y / self.cell_size..=y.saturating_add(height) / self.cell_size,
         )
     }
 }
 
 impl Raft {
-    pub fn update_position(&mut self, curr_tick: u32) {
+    pub fn update_position(
+        &mut self,
+        fighters: &mut Components<FighterId, RaftFighter>,
+        curr_tick: u32,
+        drag: u32,
+        environment: &Environment,
+    ) {
+        // the tide lifts and lowers the raft on top of its own velocity
+        let dy = self.entity.velocity.vy + Fixed::from_int(environment.tide_delta(curr_tick));
+
         // apply velocity
-        self.entity.position.x = self
-            .entity
-            .position
-            .x
-            .saturating_add_signed(self.entity.velocity.vx);
-        self.entity.position.y = self
-            .entity
-            .position
-            .y
-            .saturating_add_signed(self.entity.velocity.vy);
-
-        for fighter in &mut *self.raft_fighters {
-            fighter.entity.position.x = fighter
-                .entity
-                .position
-                .x
-                .saturating_add_signed(self.entity.velocity.vx);
-            fighter.entity.position.y = fighter
-                .entity
-                .position
-                .y
-                .saturating_add_signed(self.entity.velocity.vy);
-        }
-
-        // apply velocity decay
-        if curr_tick % 20 == 0 {
-            self.entity.velocity.vx /= 2;
-            self.entity.velocity.vy /= 2;
+        self.move_by(fighters, self.entity.velocity.vx, dy);
+
+        // apply drag every tick so the raft slows down the same way whenever the input landed
+        self.entity.velocity.vx = apply_drag(self.entity.velocity.vx, drag);
+        self.entity.velocity.vy = apply_drag(self.entity.velocity.vy, drag);
+    }
+
+    /// Moves the raft along with its crew.
+    pub fn move_by(&mut self, fighters: &mut Components<FighterId, RaftFighter>, dx: Fixed, dy: Fixed) {
+        self.entity.position.x += dx;
+        self.entity.position.y += dy;
+
+        for &id in &self.crew {
+            if let Some(fighter) = fighters.get_mut(id) {
+                fighter.entity.position.x += dx;
+                fighter.entity.position.y += dy;
+            }
+        }
+    }
+
+    /// Pulls the raft back into its zone, stopping it at the border.
+    ///
+    /// A raft that just paddled over the border is put right back on it, one coming back
+    /// from a ram deep in enemy water paddles home at up to `max_speed` per tick.
+    pub fn return_to_zone(&mut self, fighters: &mut Components<FighterId, RaftFighter>, max_speed: i32) {
+        let x = self.entity.position.x;
+        let clamped = x.clamp(
+            Fixed::from_uint(self.zone.min_x),
+            Fixed::from_uint(self.zone.max_x.max(self.zone.min_x)),
+        );
+        if clamped != x {
+            let step = Fixed::from_int(max_speed) + self.entity.velocity.vx.abs();
+            let dx = (clamped - x).clamp(-step, step);
+            self.move_by(fighters, dx, Fixed::ZERO);
+            self.entity.velocity.vx = Fixed::ZERO;
         }
     }
+
+    /// Speed `gain` gives this raft once its mass is taken into account, a raft of
+    /// `config.standard_raft_mass` gains it all.
+    #[must_use]
+    pub fn impulse(&self, gain: i32, config: &GameConfig) -> Fixed {
+        Fixed::from_int(gain).mul_div(
+            i64::from(config.standard_raft_mass),
+            i64::from(self.mass.max(1)),
+        )
+    }
+
+    /// Accelerates the raft by (`gain_x`, `gain_y`) scaled by its mass.
+    ///
+    /// Paddling never takes the raft past `config.raft_max_speed`, but doesn't brake a raft
+    /// that is already going faster either, eg after a charge.
+    pub fn paddle(&mut self, gain_x: i32, gain_y: i32, config: &GameConfig) {
+        let (impulse_x, impulse_y) = (self.impulse(gain_x, config), self.impulse(gain_y, config));
+        let velocity = &mut self.entity.velocity;
+        let max_speed = Fixed::from_int(config.raft_max_speed);
+        velocity.vx = paddled(velocity.vx, impulse_x, max_speed);
+        velocity.vy = paddled(velocity.vy, impulse_y, max_speed);
+    }
+}
+
+fn paddled(speed: Fixed, impulse: Fixed, max_speed: Fixed) -> Fixed {
+    let target = speed + impulse;
+    let limit = max_speed.abs().max(speed.abs());
+    if target.abs() <= limit {
+        target
+    } else if target.is_negative() {
+        -limit
+    } else {
+        limit
+    }
+}
+
+/// Times in `SWEEP_ONE`ths of the move during which a span at `start` of length `size`
+/// moving by `delta` overlaps the span at `other_start` of length `other_size`.
+///
+/// Both times are floored so the result is the same on every platform. `None` if a span
+/// that doesn't move never overlaps.
+fn sweep_axis(
+    start: u32,
+    size: u32,
+    delta: i64,
+    other_start: u32,
+    other_size: u32,
+) -> Option<(i64, i64)> {
+    let (start, end) = (i64::from(start), i64::from(start) + i64::from(size));
+    let (other_start, other_end) = (
+        i64::from(other_start),
+        i64::from(other_start) + i64::from(other_size),
+    );
+    let one = i64::from(SWEEP_ONE);
+
+    match delta.cmp(&0) {
+        Ordering::Equal if start < other_end && end > other_start => Some((i64::MIN, i64::MAX)),
+        Ordering::Equal => None,
+        Ordering::Greater => Some((
+            ((other_start - end) * one).div_euclid(delta),
+            ((other_end - start) * one).div_euclid(delta),
+        )),
+        Ordering::Less => Some((
+            ((start - other_end) * one).div_euclid(-delta),
+            ((end - other_start) * one).div_euclid(-delta),
+        )),
+    }
+}
+
+/// Slows `speed` down by `drag / DRAG_ONE` of itself, rounded up so rafts always come to rest.
+fn apply_drag(speed: Fixed, drag: u32) -> Fixed {
+    let magnitude = u128::from(speed.to_bits().unsigned_abs());
+    let loss = (magnitude * u128::from(drag))
+        .div_ceil(u128::from(DRAG_ONE))
+        .min(magnitude);
+    // loss <= |speed| so it only saturates for Fixed::MIN
+    let loss = Fixed::from_bits(i64::try_from(loss).unwrap_or(i64::MAX));
+    if speed.is_negative() {
+        speed + loss
+    } else {
+        speed - loss
+    }
 }
 
 impl Projectile {
-    pub fn update_position(&mut self, curr_tick: u32) {
-        // apply velocity
-        self.entity.position.x = self
-            .entity
-            .position
-            .x
-            .saturating_add_signed(self.entity.velocity.vx);
-
-        // apply sine wave to y position using lookup table
-        const AMPLITUDE: u32 = 100;
-        const FREQUENCY: u32 = 1;
-        // Precomputed sine wave values scaled by 1000 to avoid floating points
-        const SINE_TABLE: [i32; 8] = [0, 707, 1000, 707, 0, -707, -1000, -707];
-        let sine_value = SINE_TABLE[(curr_tick * FREQUENCY) as usize % SINE_TABLE.len()];
-        let y_offset = ((AMPLITUDE as i32 * sine_value) / 1000) as u32;
-        self.entity.position.y = self
-            .entity
-            .position
-            .y
-            .saturating_add_signed(y_offset as i32);
+    /// Bounding box of the projectile's circle if it were centred on `position`.
+    #[must_use]
+    pub const fn bounding_box_at(&self, position: &Position) -> (u32, u32, u32, u32) {
+        let diameter = 2 * self.radius;
+        // (x1, y1, w1, h1)
+        let radius = Fixed::from_uint(self.radius);
+        (
+            position.x.saturating_sub(radius).to_u32(),
+            position.y.saturating_sub(radius).to_u32(),
+            diameter,
+            diameter,
+        )
+    }
+
+    /// Where the projectile was `time` `SWEEP_ONE`ths into its move from `origin`, eg at the
+    /// contact `sweep` found.
+    #[must_use]
+    pub fn position_along(&self, origin: &Position, time: u32) -> Position {
+        let (time, one) = (i64::from(time), i64::from(SWEEP_ONE));
+        Position {
+            x: origin.x + (self.entity.position.x - origin.x).mul_div(time, one),
+            y: origin.y + (self.entity.position.y - origin.y).mul_div(time, one),
+        }
+    }
+
+    /// Bounding box of everything the projectile covered moving from `origin` to its current
+    /// position.
+    #[must_use]
+    pub fn swept_box(&self, origin: &Position) -> (u32, u32, u32, u32) {
+        let (x1, y1, w1, h1) = self.bounding_box_at(origin);
+        let (x2, y2, w2, h2) = self.bounding_box();
+        let (left, top) = (x1.min(x2), y1.min(y2));
+        let right = (x1 + w1).max(x2 + w2);
+        let bottom = (y1 + h1).max(y2 + h2);
+        (left, top, right - left, bottom - top)
+    }
+
+    /// Swept test of the projectile's last move against `other`.
+    ///
+    /// The move is taken as a straight line from `origin` to the current position, so a
+    /// projectile that passed clean through a thin target within a tick still hits it.
+    /// Bounding boxes are swept first, then the circle is checked against `other`'s shape
+    /// while the boxes overlap. Returns when they first overlap in `SWEEP_ONE`ths of the
+    /// move, 0 if they already did at `origin`, or `None` if they never do.
+    pub fn sweep<T: Collision>(&self, origin: &Position, other: &T) -> Option<u32> {
+        let (x1, y1, w1, h1) = self.bounding_box_at(origin);
+        let (x2, y2, w2, h2) = other.bounding_box();
+        let (end_x, end_y, _, _) = self.bounding_box();
+
+        let (entry_x, exit_x) = sweep_axis(x1, w1, i64::from(end_x) - i64::from(x1), x2, w2)?;
+        let (entry_y, exit_y) = sweep_axis(y1, h1, i64::from(end_y) - i64::from(y1), y2, h2)?;
+        let entry = entry_x.max(entry_y).max(0);
+        let exit = exit_x.min(exit_y).min(i64::from(SWEEP_ONE));
+
+        // touching edges don't collide, same as `collides_with`
+        if entry >= exit {
+            return None;
+        }
+
+        let shape = other.shape();
+        let scale = i128::from(SWEEP_ONE);
+        // the circle is swept between whole positions, like its shape
+        let (origin_x, origin_y) = (i128::from(origin.x.to_u32()), i128::from(origin.y.to_u32()));
+        let dx = i128::from(self.entity.position.x.to_u32()) - origin_x;
+        let dy = i128::from(self.entity.position.y.to_u32()) - origin_y;
+        let clearance = |t: i64| {
+            let t = i128::from(t);
+            shape.clearance(
+                origin_x * scale + dx * t,
+                origin_y * scale + dy * t,
+                self.radius,
+                scale,
+            )
+        };
+
+        // the clearance is convex along a straight move, so the closest approach is found by
+        // ternary search and the first contact by a binary search leading up to it
+        let (mut low, mut high) = (entry, exit);
+        while high - low > 2 {
+            let third = (high - low) / 3;
+            let (a, b) = (low + third, high - third);
+            match clearance(a).cmp(&clearance(b)) {
+                Ordering::Less => high = b - 1,
+                Ordering::Greater => low = a + 1,
+                Ordering::Equal => (low, high) = (a, b),
+            }
+        }
+        let closest = (low..=high).min_by_key(|&t| clearance(t))?;
+        if clearance(closest) >= 0 {
+            return None;
+        }
+
+        let (mut low, mut high) = (entry, closest);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if clearance(mid) < 0 {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        u32::try_from(low).ok()
+    }
+
+    pub fn update_position(&mut self, curr_tick: u32, gravity: i32, environment: &Environment) {
+        // apply velocity, the wind carries the projectile along with it
+        let wind = &environment.wind;
+        self.entity.position.x += self.entity.velocity.vx + wind.vx;
+        self.entity.position.y += self.entity.velocity.vy + wind.vy;
+
+        // apply sine wave to y position, its height depends on the gun
+        let amplitude = Fixed::from_uint(self.gun.spec().wobble_amplitude);
+        let phase = Angle::from_steps(i64::from(curr_tick) * WOBBLE_STEPS_PER_TICK);
+        self.entity.position.y += amplitude.saturating_mul(phase.sin());
 
         // let frequency = 0.1; // Adjust the frequency of the sine wave
         // self.entity.position.y = self.entity.position.y + (amplitude as f32 * (curr_tick as f32 * frequency).sin()) as u32;
 
-        // apply velocity decay
+        // apply gravity, ballistic projectiles rise on their launch speed and then fall back down
+        if self.gun.spec().ballistic {
+            self.entity.velocity.vy -= Fixed::from_int(gravity);
+        }
+
+        // apply velocity decay, a unit at a time without overshooting 0
         if curr_tick % 50 == 0 {
-            match self.entity.velocity.vx.cmp(&0) {
-                Ordering::Less => self.entity.velocity.vx += 1,
-                Ordering::Greater => self.entity.velocity.vx -= 1,
-                Ordering::Equal => (),
+            let vx = self.entity.velocity.vx;
+            self.entity.velocity.vx = match vx.cmp(&Fixed::ZERO) {
+                Ordering::Less => (vx + Fixed::ONE).min(Fixed::ZERO),
+                Ordering::Greater => (vx - Fixed::ONE).max(Fixed::ZERO),
+                Ordering::Equal => vx,
             };
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts;
+    use crate::paddle::PlayerId;
+    use crate::rng::Rng;
+    use crate::world::{Entity, GameState, GunTypes};
+
+    #[test]
+    fn it_sweeps_projectiles_through_thin_targets() {
+        let state = GameState::new();
+        let fighter = state.fighters[state.rafts[PlayerId(1).raft()].crew[0]].clone();
+        let (x, y, width, height) = fighter.bounding_box();
+        let origin = Position {
+            x: Fixed::from_uint(x - 200),
+            y: Fixed::from_uint(y + height / 2),
+        };
+        let mut shot = Projectile::new(
+            Entity {
+                position: origin.clone(),
+                velocity: consts::NO_VELOCITY,
+                is_active: true,
+            },
+            consts::DEFAULT_PROJECTILE_RADIUS,
+            GunTypes::StraightShooter,
+            0,
+        );
+
+        // jumps clean over the fighter within a single move
+        shot.entity.position.x = Fixed::from_uint(x + width + 200);
+        assert!(!shot.collides_with(&fighter));
+        let entry = shot.sweep(&origin, &fighter).unwrap();
+        assert!(entry > 0 && entry < SWEEP_ONE / 2);
+
+        // the same move but passing above the fighter
+        let above = Position {
+            x: origin.x,
+            y: Fixed::from_uint(y - 2 * shot.radius),
+        };
+        shot.entity.position.y = above.y;
+        assert_eq!(shot.sweep(&above, &fighter), None);
+
+        // already overlapping at the start of the move
+        let inside = Position {
+            x: Fixed::from_uint(x + width / 2),
+            y: origin.y,
+        };
+        shot.entity.position = inside.clone();
+        assert_eq!(shot.sweep(&inside, &fighter), Some(0));
+    }
+
+    #[test]
+    fn it_only_hits_with_the_round_part_of_a_projectile() {
+        let state = GameState::new();
+        let fighter = state.fighters[state.rafts[PlayerId(1).raft()].crew[0]].clone();
+        let (x, y, width, _) = fighter.bounding_box();
+        let radius = consts::DEFAULT_PROJECTILE_RADIUS;
+        let near = |dx: i32, dy: i32| Position {
+            x: Fixed::from_uint(x + width) + Fixed::from_int(dx),
+            y: Fixed::from_uint(y) + Fixed::from_int(dy),
+        };
+        let quarter = i32::try_from(radius / 4).unwrap();
+
+        // the bounding boxes overlap at the fighter's top right corner, the circle doesn't
+        let corner = near(3 * quarter, -3 * quarter);
+        let mut shot = Projectile::new(
+            Entity {
+                position: corner.clone(),
+                velocity: consts::NO_VELOCITY,
+                is_active: true,
+            },
+            radius,
+            GunTypes::StraightShooter,
+            0,
+        );
+        assert!(!shot.collides_with(&fighter));
+        assert!(!fighter.collides_with(&shot));
+        assert_eq!(shot.sweep(&corner, &fighter), None);
+
+        // straight above the edge the box and the circle agree
+        let edge = near(-1, -3 * quarter);
+        shot.entity.position = edge.clone();
+        assert!(shot.collides_with(&fighter));
+        assert_eq!(shot.sweep(&edge, &fighter), Some(0));
+
+        // gliding past the corner never touches it, even though the boxes overlap on the way
+        let start = near(-quarter, -7 * quarter);
+        shot.entity.position = near(7 * quarter, quarter);
+        assert_eq!(shot.sweep(&start, &fighter), None);
+
+        // skimming along the top edge does
+        let start = near(-8 * quarter - i32::try_from(width).unwrap(), -2 * quarter);
+        shot.entity.position = near(8 * quarter, -2 * quarter);
+        assert!(shot.sweep(&start, &fighter).is_some_and(|entry| entry > 0));
+    }
+
+    #[test]
+    fn it_files_every_overlapping_box_in_the_grid() {
+        let mut rng = Rng::new(7);
+        let mut random_box = |max: u32| {
+            (
+                rng.below(10_000),
+                rng.below(10_000),
+                rng.below(max) + 1,
+                rng.below(max) + 1,
+            )
+        };
+        let boxes: Vec<_> = (0..200).map(|_| random_box(2_000)).collect();
+        let mut grid = SpatialGrid::new(500);
+        for (key, &bounding_box) in boxes.iter().enumerate() {
+            grid.insert(key, bounding_box);
+        }
+
+        for _ in 0..200 {
+            let area = random_box(1_000);
+            let (x, y, w, h) = area;
+            let candidates = grid.query(area);
+            assert!(candidates.windows(2).all(|pair| pair[0] < pair[1]));
+            for (key, &(x2, y2, w2, h2)) in boxes.iter().enumerate() {
+                if x < x2 + w2 && x + w > x2 && y < y2 + h2 && y + h > y2 {
+                    assert!(candidates.contains(&key));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,383 @@
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::errors::{Result, SimulationError};
+use crate::paddle::{encode_length_prefixed, Action, PlayerId};
+use crate::schema;
+use crate::world::GameState;
+
+/// Compact copy of a `GameState`, its CBOR encoding.
+///
+/// Saving one encodes the whole state, as much work as `GameState::to_serialized_state`,
+/// but into a buffer that is reused when the snapshot is overwritten. Restoring decodes it
+/// all again. Events aren't encoded and restore empty.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    frame: u32,
+    encoded: Vec<u8>,
+}
+
+impl Snapshot {
+    /// Frame the snapshot was saved for.
+    #[must_use]
+    pub const fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the encoding doesn't decode back into a state
+    pub fn restore(&self) -> Result<GameState> {
+        GameState::from_serialized_state(&self.encoded)
+    }
+
+    fn save(&mut self, frame: u32, state: &GameState) -> Result<()> {
+        self.frame = frame;
+        self.encoded.clear();
+        schema::encode_state(state, &mut self.encoded)
+    }
+}
+
+/// Ring buffer of the snapshots saved for the last `capacity` frames.
+///
+/// A frame is whatever the caller steps the simulation by, a tick or a whole input window.
+/// Frame `n` lives in slot `n % capacity`, so saving a frame again replaces its snapshot.
+#[derive(Debug, Clone)]
+pub struct SnapshotRing {
+    snapshots: Vec<Option<Snapshot>>,
+}
+
+impl SnapshotRing {
+    /// Ring keeping at least one snapshot.
+    #[must_use]
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            snapshots: vec![None; capacity.max(1) as usize],
+        }
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> u32 {
+        u32::try_from(self.snapshots.len()).unwrap_or(u32::MAX)
+    }
+
+    /// Saves `state` as `frame`, overwriting the frame `capacity` frames before it.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the state fails to encode
+    pub fn save(&mut self, frame: u32, state: &GameState) -> Result<()> {
+        let slot = (frame % self.capacity()) as usize;
+        self.snapshots[slot].get_or_insert_with(Snapshot::default).save(frame, state)
+    }
+
+    /// Snapshot of `frame`, `None` if it was never saved or already overwritten.
+    #[must_use]
+    pub fn get(&self, frame: u32) -> Option<&Snapshot> {
+        self.snapshots[(frame % self.capacity()) as usize]
+            .as_ref()
+            .filter(|snapshot| snapshot.frame == frame)
+    }
+}
+
+/// Late input that didn't match the prediction a window was simulated with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correction {
+    pub player: PlayerId,
+    /// Input window that was mispredicted
+    pub window: u32,
+    /// Tick the state was rolled back to
+    pub tick: u32,
+    /// Ticks simulated again to catch back up
+    pub resimulated: u32,
+}
+
+/// Runs a match ahead of the inputs of remote players.
+///
+/// The session advances one input window at a time. Players whose input for a window
+/// hasn't arrived yet are predicted to repeat their last known window. When an input
+/// arrives for a window that already ran with a different prediction, the state is
+/// restored from that window's snapshot and simulated again up to the present.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone)]
+pub struct RollbackSession {
+    state: GameState,
+    snapshots: SnapshotRing,
+    /// Windows received from each player, indexed by `PlayerId::index`
+    received: Vec<BTreeMap<u32, Vec<u32>>>,
+    /// Inputs each window ran with, until it is confirmed
+    simulated: BTreeMap<u32, Vec<Vec<u32>>>,
+    /// Next window to simulate
+    window: u32,
+    /// First window some player's input is still missing for
+    confirmed: u32,
+}
+
+impl RollbackSession {
+    /// Starts a session at window 0 that runs at most `max_prediction` windows ahead of
+    /// the inputs every player confirmed.
+    #[must_use]
+    pub fn new(state: GameState, max_prediction: u32) -> Self {
+        Self {
+            snapshots: SnapshotRing::new(max_prediction),
+            received: vec![BTreeMap::new(); state.rafts.len()],
+            simulated: BTreeMap::new(),
+            window: 0,
+            confirmed: 0,
+            state,
+        }
+    }
+
+    /// State after the last simulated window, predictions included.
+    #[must_use]
+    pub const fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Next window `advance` simulates.
+    #[must_use]
+    pub const fn window(&self) -> u32 {
+        self.window
+    }
+
+    /// First window that is still missing the input of some player.
+    #[must_use]
+    pub const fn confirmed_window(&self) -> u32 {
+        self.confirmed
+    }
+
+    /// Records the actions `player` took in `window`.
+    ///
+    /// The first input received for a window wins, later ones are ignored. If the window was
+    /// already simulated with a different prediction it is rolled back and the correction
+    /// is returned.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `player` isn't in the match, `actions` isn't a valid window, the
+    /// snapshot to roll back to is missing or fails to restore, or simulating again fails
+    /// for another reason than the match finishing
+    pub fn add_input(&mut self, player: PlayerId, window: u32, actions: Vec<u32>) -> Result<Option<Correction>> {
+        let players = self.received.len();
+        let Some(received) = self.received.get_mut(player.index()) else {
+            return Err(SimulationError::UnknownPlayer {
+                received: player.0,
+                players: u32::try_from(players).unwrap_or(u32::MAX),
+            });
+        };
+        let max = self.state.config.tick_input_api_chunk_size;
+        let len = u32::try_from(actions.len()).unwrap_or(u32::MAX);
+        if len > max {
+            return Err(SimulationError::InputWindowTooLong {
+                window,
+                received: len,
+                max,
+            });
+        }
+        for &action in &actions {
+            Action::from(action)?;
+        }
+
+        if window < self.confirmed || received.contains_key(&window) {
+            return Ok(None);
+        }
+        let mispredicted = window < self.window
+            && self
+                .simulated
+                .get(&window)
+                .is_some_and(|inputs| inputs[player.index()] != actions);
+        received.insert(window, actions);
+
+        let correction = if mispredicted {
+            Some(self.rollback(player, window)?)
+        } else {
+            None
+        };
+        self.confirm();
+        Ok(correction)
+    }
+
+    /// Simulates the next window, predicting the input of players it hasn't arrived from.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the session is already `max_prediction` windows ahead of the
+    /// confirmed inputs, if the match is finished or if the state fails to snapshot
+    pub fn advance(&mut self) -> Result<()> {
+        if self.window.saturating_sub(self.confirmed) >= self.snapshots.capacity() {
+            return Err(SimulationError::PredictionTooFar {
+                window: self.window,
+                confirmed: self.confirmed,
+            });
+        }
+        self.simulate()?;
+        self.confirm();
+        Ok(())
+    }
+
+    /// Input `player` is assumed to send in `window`: the one received or else a repeat
+    /// of their latest one before it.
+    fn input(&self, player: usize, window: u32) -> Vec<u32> {
+        self.received[player]
+            .range(..=window)
+            .next_back()
+            .map(|(_, actions)| actions.clone())
+            .unwrap_or_default()
+    }
+
+    fn simulate(&mut self) -> Result<()> {
+        self.snapshots.save(self.window, &self.state)?;
+        let inputs: Vec<Vec<u32>> = (0..self.received.len())
+            .map(|player| self.input(player, self.window))
+            .collect();
+        let streams: Vec<Vec<u32>> = inputs.iter().map(|input| encode_length_prefixed(&[input])).collect();
+        let streams: Vec<&[u32]> = streams.iter().map(Vec::as_slice).collect();
+
+        self.state.tick(self.state.config.ticks_per_input, &streams)?;
+        self.simulated.insert(self.window, inputs);
+        self.window += 1;
+        Ok(())
+    }
+
+    /// Restores the state `window` started from and simulates it again up to the present.
+    fn rollback(&mut self, player: PlayerId, window: u32) -> Result<Correction> {
+        // every window from the confirmed one on keeps its snapshot, see `advance`
+        let Some(snapshot) = self.snapshots.get(window) else {
+            return Err(SimulationError::PredictionTooFar {
+                window,
+                confirmed: self.confirmed,
+            });
+        };
+        self.state = snapshot.restore()?;
+        let tick = self.state.ticks;
+
+        let present = self.window;
+        self.window = window;
+        while self.window < present {
+            match self.simulate() {
+                Ok(()) => {}
+                // the corrected timeline may finish the match earlier
+                Err(SimulationError::MatchFinished { .. }) => {
+                    self.simulated.split_off(&self.window);
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Correction {
+            player,
+            window,
+            tick,
+            resimulated: self.state.ticks - tick,
+        })
+    }
+
+    /// Moves past every window all players' input arrived for and forgets what can no
+    /// longer be rolled back.
+    fn confirm(&mut self) {
+        while self.received.iter().all(|received| received.contains_key(&self.confirmed)) {
+            self.confirmed += 1;
+        }
+        self.simulated = self.simulated.split_off(&self.confirmed);
+
+        // the window before the next one to simulate is kept to predict from
+        let stale = self.confirmed.min(self.window).saturating_sub(1);
+        for received in &mut self.received {
+            *received = received.split_off(&stale);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows(actions: &[&[Action]]) -> Vec<Vec<u32>> {
+        actions
+            .iter()
+            .map(|window| window.iter().map(Action::to_u32).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_restores_snapshots_until_they_are_overwritten() {
+        let mut state = GameState::new();
+        let mut ring = SnapshotRing::new(2);
+        let idle = encode_length_prefixed(&[[]; 1]);
+        let mut saved = vec![];
+        for frame in 0..3 {
+            ring.save(frame, &state).unwrap();
+            saved.push(state.to_serialized_state().unwrap());
+            state.tick(state.config.ticks_per_input, &[&idle, &idle]).unwrap();
+        }
+
+        assert!(ring.get(0).is_none());
+        for frame in 1..3 {
+            let snapshot = ring.get(frame).unwrap();
+            assert_eq!(snapshot.frame(), frame);
+            assert_eq!(snapshot.restore().unwrap().to_serialized_state().unwrap(), saved[frame as usize]);
+        }
+        assert!(ring.get(3).is_none());
+    }
+
+    #[test]
+    fn it_rolls_back_mispredicted_windows() {
+        let local = windows(&[&[Action::Fire], &[Action::MoveUp], &[], &[Action::Fire]]);
+        let remote = windows(&[&[], &[Action::MoveDown, Action::Fire]]);
+        let mut session = RollbackSession::new(GameState::new(), 8);
+
+        for (window, actions) in (0..).zip(&local) {
+            assert_eq!(session.add_input(PlayerId(0), window, actions.clone()).unwrap(), None);
+            session.advance().unwrap();
+        }
+        assert_eq!(session.confirmed_window(), 0);
+
+        // nothing was known from the remote player so an empty window was right
+        assert_eq!(session.add_input(PlayerId(1), 0, remote[0].clone()).unwrap(), None);
+        assert_eq!(session.confirmed_window(), 1);
+
+        let ticks_per_input = session.state().config.ticks_per_input;
+        let correction = session.add_input(PlayerId(1), 1, remote[1].clone()).unwrap();
+        assert_eq!(
+            correction,
+            Some(Correction {
+                player: PlayerId(1),
+                window: 1,
+                tick: ticks_per_input,
+                resimulated: 3 * ticks_per_input,
+            })
+        );
+        assert_eq!(session.confirmed_window(), 2);
+        assert_eq!(session.window(), 4);
+
+        // the remote player is predicted to keep repeating their last window
+        let remote = [&remote[0], &remote[1], &remote[1], &remote[1]];
+        let mut expected = GameState::new();
+        let streams = [encode_length_prefixed(&local), encode_length_prefixed(&remote)];
+        expected.tick(4 * ticks_per_input, &[&streams[0], &streams[1]]).unwrap();
+        assert_eq!(session.state().to_serialized_state().unwrap(), expected.to_serialized_state().unwrap());
+    }
+
+    #[test]
+    fn it_stops_predicting_past_its_snapshots() {
+        let mut session = RollbackSession::new(GameState::new(), 2);
+        session.advance().unwrap();
+        session.advance().unwrap();
+        assert!(matches!(
+            session.advance(),
+            Err(SimulationError::PredictionTooFar { window: 2, confirmed: 0 })
+        ));
+
+        for player in [PlayerId(0), PlayerId(1)] {
+            session.add_input(player, 0, vec![]).unwrap();
+        }
+        session.advance().unwrap();
+        assert!(matches!(
+            session.add_input(PlayerId(2), 0, vec![]),
+            Err(SimulationError::UnknownPlayer { received: 2, players: 2 })
+        ));
+    }
+}
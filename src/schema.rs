@@ -0,0 +1,336 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use minicbor::data::Type;
+use minicbor::decode;
+use minicbor::{Decoder, Encoder};
+
+use crate::errors::{Result, SimulationError};
+use crate::world::GameState;
+
+/// Layout version of the serialized `GameState`, bump it with every change to an encoded
+/// field and register the migration from the previous version in `MIGRATIONS`.
+pub const STATE_VERSION: u32 = 3;
+
+/// First version with the envelope. States encoded before it carry no version to migrate
+/// from and are rejected.
+pub const OLDEST_STATE_VERSION: u32 = 1;
+
+/// Rewrites an encoded state body of one version into the layout of the next one.
+///
+/// Migrations work on the CBOR bytes rather than on `GameState`, so they keep compiling
+/// after the types move on to newer layouts.
+pub type Migration = fn(&[u8]) -> Result<Vec<u8>>;
+
+/// `MIGRATIONS[v - OLDEST_STATE_VERSION]` upgrades a body of version `v` to version `v + 1`.
+pub const MIGRATIONS: [Migration; (STATE_VERSION - OLDEST_STATE_VERSION) as usize] =
+    [aim_at_angles, configure_mass_and_armor];
+
+/// Encodes `state` into `buffer` as the envelope `[STATE_VERSION, state]`.
+///
+/// The version is part of the bytes that are hashed, so states of different layouts never
+/// share a hash.
+///
+/// # Errors
+///
+/// Will return `Err` if some part of the state fails to encode
+pub fn encode_state(state: &GameState, buffer: &mut Vec<u8>) -> Result<()> {
+    Encoder::new(buffer).array(2)?.u32(STATE_VERSION)?.encode(state)?;
+    Ok(())
+}
+
+/// Decodes a state of any version from `OLDEST_STATE_VERSION` to `STATE_VERSION`,
+/// migrating older ones.
+///
+/// # Errors
+///
+/// Will return `Err` if the version is unversioned, newer than `STATE_VERSION`, a
+/// migration fails, the bytes aren't a valid state or its config fails
+/// `GameConfig::validate`
+pub fn decode_state(encoded: &[u8]) -> Result<GameState> {
+    let mut decoder = Decoder::new(encoded);
+    let version = envelope_version(&mut decoder)?;
+    if !(OLDEST_STATE_VERSION..=STATE_VERSION).contains(&version) {
+        return Err(SimulationError::UnsupportedStateVersion {
+            received: version,
+            oldest: OLDEST_STATE_VERSION,
+            latest: STATE_VERSION,
+        });
+    }
+    let state: GameState = if version == STATE_VERSION {
+        // decoded in place so error positions point into `encoded`
+        decoder.decode()?
+    } else {
+        let pending = &MIGRATIONS[(version - OLDEST_STATE_VERSION) as usize..];
+        let body = upgrade(&encoded[decoder.position()..], pending)?;
+        minicbor::decode(&body)?
+    };
+    // the config came from outside, hold it to the same bounds as `GameState::with_config`
+    state.config.validate()?;
+    Ok(state)
+}
+
+/// Reads the version off the envelope, leaving `decoder` at the start of the state body.
+fn envelope_version(decoder: &mut Decoder) -> Result<u32> {
+    // states from before the envelope are a bare map without a version
+    if decoder.datatype()? == Type::Map {
+        return Ok(0);
+    }
+    let position = decoder.position();
+    if decoder.array()? != Some(2) {
+        return Err(decode::Error::message("expected a [version, state] envelope").at(position).into());
+    }
+    Ok(decoder.u32()?)
+}
+
+/// Version 2 aims fighters along any `trig::Angle` instead of one of eight bearings and
+/// adds the config's `aim_step`.
+fn aim_at_angles(body: &[u8]) -> Result<Vec<u8>> {
+    // the default aim step when version 2 was introduced
+    let aim_step = minicbor::to_vec(16_u32)?;
+    let mut out = Vec::with_capacity(body.len());
+    copy_map(&mut Decoder::new(body), &mut out, &[], |key, d, out| match key {
+        // config
+        3 => copy_map(d, out, &[(27, &aim_step)], |_, _, _| Ok(false)).map(|()| true),
+        // fighters, slots of `{0: generation, 1: fighter}`
+        6 => copy_array(d, out, |d, out| {
+            copy_map(d, out, &[], |key, d, out| {
+                if key != 1 || d.datatype()? == Type::Null {
+                    return Ok(false);
+                }
+                // the fighter's aim
+                copy_map(d, out, &[], |key, d, out| match key {
+                    7 => bearing_to_angle(d, out).map(|()| true),
+                    _ => Ok(false),
+                })
+                .map(|()| true)
+            })
+        })
+        .map(|()| true),
+        _ => Ok(false),
+    })?;
+    Ok(out)
+}
+
+/// Version 3 moves the broadphase cell size, the standard raft mass and the armor of new
+/// rafts and fighters into the config.
+fn configure_mass_and_armor(body: &[u8]) -> Result<Vec<u8>> {
+    // the values of `consts` when version 3 was introduced
+    let grid_cell_size = minicbor::to_vec(500_u32)?;
+    let standard_raft_mass = minicbor::to_vec(100_u32)?;
+    let raft_armor = encode_armor(2, [25, 0, -25, 0])?;
+    let fighter_armor = encode_armor(0, [0; 4])?;
+    let appended: [(u32, &[u8]); 4] = [
+        (28, &grid_cell_size),
+        (29, &standard_raft_mass),
+        (30, &raft_armor),
+        (31, &fighter_armor),
+    ];
+
+    let mut out = Vec::with_capacity(body.len() + 64);
+    copy_map(&mut Decoder::new(body), &mut out, &[], |key, d, out| match key {
+        // config
+        3 => copy_map(d, out, &appended, |_, _, _| Ok(false)).map(|()| true),
+        _ => Ok(false),
+    })?;
+    Ok(out)
+}
+
+/// `Armor` as `{0: rating, 1: resistances}`, resistances keyed by `DamageType` order.
+fn encode_armor(rating: u32, resistances: [i32; 4]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut encoder = Encoder::new(&mut out);
+    encoder.map(2)?.u32(0)?.u32(rating)?.u32(1)?.map(4)?;
+    for (key, resistance) in (0..).zip(resistances) {
+        encoder.u32(key)?.i32(resistance)?;
+    }
+    Ok(out)
+}
+
+/// Rewrites a version 1 `Bearings`, `[variant, {}]`, as the steps of the same angle.
+fn bearing_to_angle(d: &mut Decoder, out: &mut Vec<u8>) -> Result<()> {
+    let position = d.position();
+    let fields = d.array()?.unwrap_or(0);
+    let steps: u16 = match d.u32()? {
+        0 => 256, // North
+        1 => 768, // South
+        2 => 0,   // East
+        3 => 512, // West
+        4 => 128, // Northeast
+        5 => 384, // Northwest
+        6 => 896, // Southeast
+        7 => 640, // Southwest
+        _ => return Err(decode::Error::message("unknown bearing").at(position).into()),
+    };
+    for _ in 1..fields {
+        d.skip()?;
+    }
+    Encoder::new(out).u16(steps)?;
+    Ok(())
+}
+
+/// Copies the next item unchanged.
+fn copy_item(d: &mut Decoder, out: &mut Vec<u8>) -> Result<()> {
+    let start = d.position();
+    d.skip()?;
+    out.extend_from_slice(&d.input()[start..d.position()]);
+    Ok(())
+}
+
+/// Copies a map with `u32` keys followed by the already encoded `appended` entries.
+///
+/// `value` is handed each key with the decoder at its value. It either writes the value
+/// itself and returns `true`, or returns `false` to have it copied unchanged.
+fn copy_map<'b>(
+    d: &mut Decoder<'b>,
+    out: &mut Vec<u8>,
+    appended: &[(u32, &[u8])],
+    mut value: impl FnMut(u32, &mut Decoder<'b>, &mut Vec<u8>) -> Result<bool>,
+) -> Result<()> {
+    let position = d.position();
+    let Some(len) = d.map()? else {
+        return Err(decode::Error::message("expected a map of known length").at(position).into());
+    };
+    Encoder::new(&mut *out).map(len + appended.len() as u64)?;
+    for _ in 0..len {
+        let key = d.u32()?;
+        Encoder::new(&mut *out).u32(key)?;
+        if !value(key, d, out)? {
+            copy_item(d, out)?;
+        }
+    }
+    for (key, encoded) in appended {
+        Encoder::new(&mut *out).u32(*key)?;
+        out.extend_from_slice(encoded);
+    }
+    Ok(())
+}
+
+/// Copies an array, `item` writes each of its items.
+fn copy_array<'b>(
+    d: &mut Decoder<'b>,
+    out: &mut Vec<u8>,
+    mut item: impl FnMut(&mut Decoder<'b>, &mut Vec<u8>) -> Result<()>,
+) -> Result<()> {
+    let position = d.position();
+    let Some(len) = d.array()? else {
+        return Err(decode::Error::message("expected an array of known length").at(position).into());
+    };
+    Encoder::new(&mut *out).array(len)?;
+    for _ in 0..len {
+        item(d, out)?;
+    }
+    Ok(())
+}
+
+/// Runs `migrations` over `body` in order.
+fn upgrade(body: &[u8], migrations: &[Migration]) -> Result<Vec<u8>> {
+    let mut body = body.to_vec();
+    for migrate in migrations {
+        body = migrate(&body)?;
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+    use crate::world::Bearings;
+    use alloc::vec;
+
+    #[test]
+    fn it_rejects_states_from_before_the_envelope() {
+        let mut state = GameState::new();
+        state.ticks = 42;
+        let versioned = state.to_serialized_state().unwrap();
+        assert_eq!(GameState::from_serialized_state(&versioned).unwrap().ticks, 42);
+
+        // there is no telling which layout a bare state map was encoded with
+        let bare = minicbor::to_vec(&state).unwrap();
+        assert!(matches!(
+            GameState::from_serialized_state(&bare),
+            Err(SimulationError::UnsupportedStateVersion { received: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn it_runs_migrations_in_order() {
+        let migrations: [Migration; 2] = [
+            |body| Ok([body, &[0]].concat()),
+            |body| Ok([body, &[1]].concat()),
+        ];
+        assert_eq!(upgrade(&[7], &migrations).unwrap(), vec![7, 0, 1]);
+        assert_eq!(upgrade(&[7], &migrations[1..]).unwrap(), vec![7, 1]);
+        assert_eq!(upgrade(&[7], &[]).unwrap(), vec![7]);
+    }
+
+    #[test]
+    fn it_migrates_bearing_aims_to_angles() {
+        // a version 1 state one window in, with every fighter turned away from its spawn aim
+        let state = GameState::from_serialized_state(include_bytes!("../fixtures/state-v1.cbor"))
+            .unwrap();
+        assert_eq!(state.ticks, 5);
+        assert_eq!(state.config.aim_step, 16);
+
+        let aims: Vec<_> = state.fighters.values().map(|fighter| fighter.aim).collect();
+        let expected = [Bearings::Northeast, Bearings::West, Bearings::Northwest];
+        assert_eq!(aims, expected.map(|bearing| bearing.angle()));
+
+        let reencoded = state.to_serialized_state().unwrap();
+        let decoded = GameState::from_serialized_state(&reencoded).unwrap();
+        assert_eq!(decoded.to_serialized_state().unwrap(), reencoded);
+    }
+
+    #[test]
+    fn it_moves_mass_and_armor_into_the_config() {
+        // a version 2 state one window in, fighters aiming off the eight bearings
+        let state = GameState::from_serialized_state(include_bytes!("../fixtures/state-v2.cbor"))
+            .unwrap();
+        assert_eq!(state.ticks, 5);
+        let aims: Vec<_> = state.fighters.values().map(|fighter| fighter.aim.steps()).collect();
+        assert_eq!(aims, [16, 512, 496]);
+
+        let defaults = GameConfig::default();
+        assert_eq!(state.config.grid_cell_size, defaults.grid_cell_size);
+        assert_eq!(state.config.standard_raft_mass, defaults.standard_raft_mass);
+        assert_eq!(state.config.raft_armor, defaults.raft_armor);
+        assert_eq!(state.config.fighter_armor, defaults.fighter_armor);
+    }
+
+    #[test]
+    fn it_validates_the_decoded_config() {
+        let mut state = GameState::new();
+        state.config.ticks_per_input = 0;
+        let encoded = state.to_serialized_state().unwrap();
+        assert!(matches!(
+            GameState::from_serialized_state(&encoded),
+            Err(SimulationError::InvalidConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn it_rejects_versions_from_the_future() {
+        let state = GameState::new();
+        let mut encoded = Vec::new();
+        Encoder::new(&mut encoded)
+            .array(2)
+            .unwrap()
+            .u32(STATE_VERSION + 1)
+            .unwrap()
+            .encode(&state)
+            .unwrap();
+        assert!(matches!(
+            GameState::from_serialized_state(&encoded),
+            Err(SimulationError::UnsupportedStateVersion { received, latest: STATE_VERSION, .. })
+                if received == STATE_VERSION + 1
+        ));
+
+        let unwrapped = minicbor::to_vec((STATE_VERSION,)).unwrap();
+        assert!(matches!(
+            GameState::from_serialized_state(&unwrapped),
+            Err(SimulationError::StateDecode { position: Some(0), .. })
+        ));
+    }
+}
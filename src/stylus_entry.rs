@@ -1,5 +1,6 @@
 extern crate alloc;
 
+use alloc::string::ToString;
 use alloc::vec::Vec;
 use alloc::vec;
 /// Import items from the SDK. The prelude contains common traits and macros.
@@ -12,6 +13,7 @@ use stylus_sdk::{
     abi::Bytes
 };
 
+use crate::errors::SimulationError;
 use crate::world::GameState;
 
 // Define some persistent storage using the Solidity ABI.
@@ -34,60 +36,102 @@ impl PaddleBattle {
         self.game_state_hash.get()
     }
 
-    pub fn tick(&mut self, num_ticks: u32, inputs: Vec<u32>) {
+    /// Plays `num_ticks` of a new default duel, see `_tick`.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with the error message if an input stream isn't a valid series of windows or
+    /// the match finishes before the last tick
+    // the ABI hands over owned vectors
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn tick(&mut self, num_ticks: u32, left_inputs: Vec<u32>, right_inputs: Vec<u32>) -> Result<(), Vec<u8>> {
         let mut curr_game_state = GameState::new();
         _tick(
             num_ticks,
-            &inputs,
+            &left_inputs,
+            &right_inputs,
             &mut curr_game_state,
             &mut self.game_state_hash,
-        );
+        )
     }
 
-    pub fn load_and_tick(&mut self, num_ticks: u32, inputs: Vec<u32>, serialized_state: Bytes) {
-        let mut curr_game_state = GameState::from_serialized_state(&serialized_state.to_vec());
+    /// Resumes the match in `serialized_state` and plays `num_ticks` more of it, see `_tick`.
+    ///
+    /// # Errors
+    ///
+    /// Reverts with the error message if the state fails to decode, its config is invalid,
+    /// it doesn't hash to the stored state hash, an input stream isn't a valid series of
+    /// windows or the match finishes before the last tick
+    // the ABI hands over owned values
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn load_and_tick(
+        &mut self,
+        num_ticks: u32,
+        left_inputs: Vec<u32>,
+        right_inputs: Vec<u32>,
+        serialized_state: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        // malformed states revert with the decode error instead of an opaque panic
+        let mut curr_game_state =
+            GameState::from_serialized_state(&serialized_state).map_err(|e| revert_data(&e))?;
         // let mut curr_game_state = GameState::new();
         let prev_hash = self.game_state_hash();
-        if prev_hash != curr_game_state.hash() {
-            panic!("Previous game state hash mismatch");
+        if prev_hash != curr_game_state.hash().map_err(|e| revert_data(&e))? {
+            return Err(b"Previous game state hash mismatch".to_vec());
         }
 
         _tick(
             num_ticks,
-            &inputs,
+            &left_inputs,
+            &right_inputs,
             &mut curr_game_state,
             &mut self.game_state_hash,
-        );
+        )
     }
 }
 
+/// Ticks `curr_game_state`, stores its new hash and logs the result.
+///
+/// # Errors
+///
+/// Returns the revert data if the inputs fail `validate_inputs`, an input stream isn't a
+/// valid series of windows, the match is already finished or the state fails to hash
 fn _tick(
     num_ticks: u32,
-    inputs: &Vec<u32>,
+    left_inputs: &Vec<u32>,
+    right_inputs: &Vec<u32>,
     curr_game_state: &mut GameState,
     game_state_hash_storage: &mut StorageFixedBytes<32>,
-) {
-    if !validate_inputs(&inputs) {
-        panic!("invalid inputs");
+) -> Result<(), Vec<u8>> {
+    if !validate_inputs(left_inputs) || !validate_inputs(right_inputs) {
+        return Err(b"invalid inputs".to_vec());
     }
 
     curr_game_state
-        .tick(num_ticks, &inputs)
-        .unwrap_or_else(|e| panic!("SimulationError: {:?}", e));
+        .tick(num_ticks, &[left_inputs, right_inputs])
+        .map_err(|e| revert_data(&e))?;
 
-    let new_hash = curr_game_state.hash();
+    let new_hash = curr_game_state.hash().map_err(|e| revert_data(&e))?;
     game_state_hash_storage.set(new_hash);
 
     evm::log(GameStateEvent {
         game_state_hash: new_hash,
-        left_raft_health: U256::from(curr_game_state.raft_left.curr_health),
-        right_raft_health: U256::from(curr_game_state.raft_right.curr_health),
-        left_projectile_count: U256::from(curr_game_state.left_projectiles.len()),
-        right_projectile_count: U256::from(curr_game_state.right_projectiles.len()),
+        // the contract always plays the default duel, left is team 0 and right is team 1
+        left_raft_health: U256::from(curr_game_state.team_health(0)),
+        right_raft_health: U256::from(curr_game_state.team_health(1)),
+        left_projectile_count: U256::from(curr_game_state.projectile_count(0)),
+        right_projectile_count: U256::from(curr_game_state.projectile_count(1)),
     });
+    Ok(())
+}
+
+/// Reverts with the error message as the return data.
+fn revert_data(error: &SimulationError) -> Vec<u8> {
+    error.to_string().into_bytes()
 }
 
 fn validate_inputs(inputs: &Vec<u32>) -> bool {
     // TODO: validate inputs. this could be a sequencer's sig or countersignatures by the players involved
+    // each player's stream can only steer their own raft, so a signature per stream is enough
     true
 }
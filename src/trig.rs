@@ -0,0 +1,238 @@
+use core::ops::{Add, Sub};
+
+use minicbor::{Decode, Encode};
+
+use crate::fixed::Fixed;
+use crate::world::Position;
+
+/// `sin` of every step of the first quarter turn, including both ends, as `Fixed` bits.
+///
+/// The other three quarters are mirrored from it, so one table serves `sin` and `cos`
+/// over the whole turn without growing the contract.
+const QUARTER_SINE: [i32; Angle::QUARTER_TURN.0 as usize + 1] = [
+    0, 402, 804, 1206, 1608, 2010, 2412, 2814, 3216, 3617,
+    4019, 4420, 4821, 5222, 5623, 6023, 6424, 6824, 7224, 7623,
+    8022, 8421, 8820, 9218, 9616, 10014, 10411, 10808, 11204, 11600,
+    11996, 12391, 12785, 13180, 13573, 13966, 14359, 14751, 15143, 15534,
+    15924, 16314, 16703, 17091, 17479, 17867, 18253, 18639, 19024, 19409,
+    19792, 20175, 20557, 20939, 21320, 21699, 22078, 22457, 22834, 23210,
+    23586, 23961, 24335, 24708, 25080, 25451, 25821, 26190, 26558, 26925,
+    27291, 27656, 28020, 28383, 28745, 29106, 29466, 29824, 30182, 30538,
+    30893, 31248, 31600, 31952, 32303, 32652, 33000, 33347, 33692, 34037,
+    34380, 34721, 35062, 35401, 35738, 36075, 36410, 36744, 37076, 37407,
+    37736, 38064, 38391, 38716, 39040, 39362, 39683, 40002, 40320, 40636,
+    40951, 41264, 41576, 41886, 42194, 42501, 42806, 43110, 43412, 43713,
+    44011, 44308, 44604, 44898, 45190, 45480, 45769, 46056, 46341, 46624,
+    46906, 47186, 47464, 47741, 48015, 48288, 48559, 48828, 49095, 49361,
+    49624, 49886, 50146, 50404, 50660, 50914, 51166, 51417, 51665, 51911,
+    52156, 52398, 52639, 52878, 53114, 53349, 53581, 53812, 54040, 54267,
+    54491, 54714, 54934, 55152, 55368, 55582, 55794, 56004, 56212, 56418,
+    56621, 56823, 57022, 57219, 57414, 57607, 57798, 57986, 58172, 58356,
+    58538, 58718, 58896, 59071, 59244, 59415, 59583, 59750, 59914, 60075,
+    60235, 60392, 60547, 60700, 60851, 60999, 61145, 61288, 61429, 61568,
+    61705, 61839, 61971, 62101, 62228, 62353, 62476, 62596, 62714, 62830,
+    62943, 63054, 63162, 63268, 63372, 63473, 63572, 63668, 63763, 63854,
+    63944, 64031, 64115, 64197, 64277, 64354, 64429, 64501, 64571, 64639,
+    64704, 64766, 64827, 64884, 64940, 64993, 65043, 65091, 65137, 65180,
+    65220, 65259, 65294, 65328, 65358, 65387, 65413, 65436, 65457, 65476,
+    65492, 65505, 65516, 65525, 65531, 65535, 65536,
+];
+
+/// Direction as a binary angle, a whole turn is `STEPS` steps.
+///
+/// Step 0 points east and angles grow counter-clockwise, the y axis points up like in
+/// `Bearings::unit`. Arithmetic wraps around the turn and every trig function is a table
+/// lookup, so native, wasm and Stylus builds agree on every result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
+#[cbor(transparent)]
+pub struct Angle(#[n(0)] u16);
+
+impl Angle {
+    pub const STEPS: u16 = 1024;
+    pub const ZERO: Self = Self(0);
+    pub const QUARTER_TURN: Self = Self(Self::STEPS / 4);
+    pub const HALF_TURN: Self = Self(Self::STEPS / 2);
+
+    /// Angle of `steps`, wrapped into a single turn.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub const fn from_steps(steps: i64) -> Self {
+        Self(steps.rem_euclid(Self::STEPS as i64) as u16)
+    }
+
+    #[must_use]
+    pub const fn steps(self) -> u16 {
+        self.0
+    }
+
+    #[must_use]
+    pub const fn sin(self) -> Fixed {
+        // decoded angles aren't guaranteed to be within a single turn
+        let steps = self.wrapped().0;
+        let quarter = Self::QUARTER_TURN.0;
+        let step = steps % quarter;
+        let bits = match steps / quarter {
+            0 => QUARTER_SINE[step as usize],
+            1 => QUARTER_SINE[(quarter - step) as usize],
+            2 => -QUARTER_SINE[step as usize],
+            _ => -QUARTER_SINE[(quarter - step) as usize],
+        };
+        Fixed::from_bits(bits as i64)
+    }
+
+    #[must_use]
+    pub const fn cos(self) -> Fixed {
+        Self(self.0.wrapping_add(Self::QUARTER_TURN.0)).wrapped().sin()
+    }
+
+    /// Direction scaled so its longer component is exactly one.
+    ///
+    /// The eight `Bearings` come out as the same steps as `Bearings::unit`, so firing along
+    /// a bearing is unchanged while any angle in between is possible.
+    #[must_use]
+    pub const fn direction(self) -> (Fixed, Fixed) {
+        let (x, y) = (self.cos(), self.sin());
+        let longest = if x.abs().to_bits() > y.abs().to_bits() { x.abs() } else { y.abs() };
+        (x.saturating_div(longest), y.saturating_div(longest))
+    }
+
+    /// Angle from `from` to `to`, east if they are the same point.
+    #[must_use]
+    pub fn towards(from: &Position, to: &Position) -> Self {
+        atan2(to.y - from.y, to.x - from.x)
+    }
+
+    /// Masked into a single turn. `STEPS` is a power of two that divides the `u16` range,
+    /// so wrapping arithmetic on the steps stays exact.
+    const fn wrapped(self) -> Self {
+        Self(self.0 & (Self::STEPS - 1))
+    }
+}
+
+/// Step closest to the direction of (`x`, `y`), east for the origin.
+///
+/// The first quadrant is binary searched in the sine table by comparing cross products,
+/// so no division or float is involved and the result is exact to the nearest step.
+#[must_use]
+pub fn atan2(y: Fixed, x: Fixed) -> Angle {
+    let (ax, ay) = (i128::from(x.to_bits()).abs(), i128::from(y.to_bits()).abs());
+    if ax == 0 && ay == 0 {
+        return Angle::ZERO;
+    }
+    // how far `step` turns past the target, negative while it is still short of it
+    let overshoot = |step: u16| {
+        let angle = Angle(step);
+        i128::from(angle.sin().to_bits()) * ax - i128::from(angle.cos().to_bits()) * ay
+    };
+
+    // last step that doesn't overshoot, then whichever neighbour is closer
+    let (mut low, mut high) = (0, Angle::QUARTER_TURN.0);
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if overshoot(mid) <= 0 {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    let step = if low < Angle::QUARTER_TURN.0 && overshoot(low + 1) < -overshoot(low) {
+        low + 1
+    } else {
+        low
+    };
+
+    let steps = i64::from(step);
+    let half = i64::from(Angle::HALF_TURN.0);
+    Angle::from_steps(match (x.is_negative(), y.is_negative()) {
+        (false, false) => steps,
+        (true, false) => half - steps,
+        (true, true) => half + steps,
+        (false, true) => -steps,
+    })
+}
+
+impl Add for Angle {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0.wrapping_add(other.0)).wrapped()
+    }
+}
+
+impl Sub for Angle {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0.wrapping_sub(other.0)).wrapped()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Bearings;
+
+    #[test]
+    fn it_looks_up_sine_and_cosine_around_the_turn() {
+        let eighth = Angle::from_steps(128);
+        assert_eq!(Angle::ZERO.sin(), Fixed::ZERO);
+        assert_eq!(Angle::ZERO.cos(), Fixed::ONE);
+        assert_eq!(Angle::QUARTER_TURN.sin(), Fixed::ONE);
+        assert_eq!(Angle::HALF_TURN.cos(), -Fixed::ONE);
+        assert_eq!(Angle::from_steps(-256).sin(), -Fixed::ONE);
+        assert_eq!(eighth.sin(), eighth.cos());
+        assert_eq!((eighth + Angle::HALF_TURN).sin(), -eighth.sin());
+        assert_eq!(Angle::ZERO - eighth, Angle::from_steps(896));
+        // out of range steps can only come from a decoded state
+        let decoded = Angle(u16::MAX);
+        assert_eq!(decoded + decoded, Angle::from_steps(1022));
+        assert_eq!(decoded.cos(), Angle::from_steps(1023).cos());
+
+        for step in 0..i64::from(Angle::STEPS) {
+            let angle = Angle::from_steps(step);
+            let (sin, cos) = (i128::from(angle.sin().to_bits()), i128::from(angle.cos().to_bits()));
+            let one = i128::from(Fixed::ONE.to_bits());
+            // the table is rounded to the nearest bit, so sin² + cos² stays within a few bits of one
+            assert!((sin * sin + cos * cos - one * one).abs() <= 2 * one);
+        }
+    }
+
+    #[test]
+    fn it_inverts_every_step_with_atan2() {
+        for step in 0..i64::from(Angle::STEPS) {
+            let angle = Angle::from_steps(step);
+            // far enough out that rounding can't tip it over to the next step
+            let (x, y) = (angle.cos().mul_div(1000, 1), angle.sin().mul_div(1000, 1));
+            assert_eq!(atan2(y, x), angle);
+        }
+        assert_eq!(atan2(Fixed::ZERO, Fixed::ZERO), Angle::ZERO);
+        assert_eq!(atan2(Fixed::from_int(-3), Fixed::ZERO), Angle::from_steps(768));
+
+        let from = Position {
+            x: Fixed::from_uint(100),
+            y: Fixed::from_uint(100),
+        };
+        let to = Position {
+            x: Fixed::from_uint(50),
+            y: Fixed::from_uint(150),
+        };
+        assert_eq!(Angle::towards(&from, &to), Bearings::Northwest.angle());
+    }
+
+    #[test]
+    fn it_matches_the_bearings_along_their_directions() {
+        for bearing in [
+            Bearings::North,
+            Bearings::South,
+            Bearings::East,
+            Bearings::West,
+            Bearings::Northeast,
+            Bearings::Northwest,
+            Bearings::Southeast,
+            Bearings::Southwest,
+        ] {
+            let (dx, dy) = bearing.unit();
+            assert_eq!(bearing.angle().direction(), (Fixed::from_int(dx), Fixed::from_int(dy)));
+        }
+    }
+}
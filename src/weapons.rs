@@ -0,0 +1,155 @@
+extern crate alloc;
+
+use alloc::string::String;
+
+use crate::damage::DamageType;
+use crate::world::{GunTypes, Style};
+
+/// Everything that sets one gun apart from another.
+///
+/// Lengths are in world units, durations in ticks and radii are multiples of
+/// `GameConfig::projectile_radius`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeaponSpec {
+    /// Ticks between two consecutive shots
+    pub fire_rate: u32,
+    pub magazine_size: u32,
+    /// Ticks needed to refill an empty magazine
+    pub reload_time: u32,
+    pub radius_multiplier: u32,
+    /// Speed along the aim when the projectile leaves the barrel
+    pub speed: i32,
+    /// Extra upward speed given to arcing shots so gravity carries them over the water
+    pub lift: i32,
+    /// Ballistic projectiles are pulled down by `GameConfig::gravity`, the others fly straight
+    pub ballistic: bool,
+    /// Height of the sine wobble applied on top of the projectile's velocity, 0 flies straight
+    pub wobble_amplitude: u32,
+    /// Damage of a direct hit before the target's armor
+    pub damage: u32,
+    pub damage_type: DamageType,
+    /// Percent chance a direct hit is critical
+    pub crit_chance: u32,
+    /// Damage of a critical hit in percent of the regular damage
+    pub crit_multiplier: u32,
+    /// Ticks a projectile stays in the air, 0 for no limit.
+    /// With a limit the damage falls off linearly over the projectile's lifetime
+    pub range: u32,
+    /// Projectiles fired per shot, spread evenly in a cone around the aim
+    pub pellets: u32,
+    /// Sideways speed added between two neighbouring pellets
+    pub pellet_spread: i32,
+    /// Radius of the area damaged around an impact, 0 for no splash
+    pub splash_radius_multiplier: u32,
+    pub splash_damage: u32,
+    /// Piercing projectiles keep flying after a hit but damage each raft only once
+    pub piercing: bool,
+    pub color: &'static str,
+}
+
+const SMG: WeaponSpec = WeaponSpec {
+    fire_rate: 20,
+    magazine_size: 30,
+    reload_time: 200,
+    radius_multiplier: 1,
+    speed: 40,
+    lift: 50,
+    ballistic: true,
+    wobble_amplitude: 100,
+    damage: 40,
+    damage_type: DamageType::Kinetic,
+    crit_chance: 5,
+    crit_multiplier: 200,
+    range: 0,
+    pellets: 1,
+    pellet_spread: 0,
+    splash_radius_multiplier: 0,
+    splash_damage: 0,
+    piercing: false,
+    color: "#00FF00",
+};
+
+const BAZOOKA: WeaponSpec = WeaponSpec {
+    fire_rate: 500,
+    magazine_size: 1,
+    reload_time: 400,
+    radius_multiplier: 2,
+    speed: 25,
+    lift: 90,
+    ballistic: true,
+    wobble_amplitude: 100,
+    damage: 200,
+    damage_type: DamageType::Explosive,
+    crit_chance: 0,
+    crit_multiplier: 100,
+    range: 0,
+    pellets: 1,
+    pellet_spread: 0,
+    splash_radius_multiplier: 6,
+    splash_damage: 100,
+    piercing: false,
+    color: "#FF0000",
+};
+
+const FLAME_THROWER: WeaponSpec = WeaponSpec {
+    fire_rate: 100,
+    magazine_size: 50,
+    reload_time: 300,
+    radius_multiplier: 1,
+    speed: 8,
+    lift: 30,
+    ballistic: true,
+    wobble_amplitude: 0,
+    damage: 150,
+    damage_type: DamageType::Fire,
+    crit_chance: 0,
+    crit_multiplier: 100,
+    range: 60,
+    pellets: 3,
+    pellet_spread: 2,
+    splash_radius_multiplier: 0,
+    splash_damage: 0,
+    piercing: false,
+    color: "#FFA500",
+};
+
+const STRAIGHT_SHOOTER: WeaponSpec = WeaponSpec {
+    fire_rate: 100,
+    magazine_size: 5,
+    reload_time: 250,
+    radius_multiplier: 1,
+    speed: 12,
+    lift: 0,
+    ballistic: false,
+    wobble_amplitude: 0,
+    damage: 100,
+    damage_type: DamageType::Kinetic,
+    crit_chance: 20,
+    crit_multiplier: 150,
+    range: 0,
+    pellets: 1,
+    pellet_spread: 0,
+    splash_radius_multiplier: 0,
+    splash_damage: 0,
+    piercing: true,
+    color: "#0000FF",
+};
+
+impl GunTypes {
+    #[must_use]
+    pub const fn spec(&self) -> &'static WeaponSpec {
+        match self {
+            Self::SMG => &SMG,
+            Self::Bazooka => &BAZOOKA,
+            Self::FlameThrower => &FLAME_THROWER,
+            Self::StraightShooter => &STRAIGHT_SHOOTER,
+        }
+    }
+
+    #[must_use]
+    pub fn style(&self) -> Style {
+        Style {
+            color: String::from(self.spec().color),
+        }
+    }
+}
@@ -1,9 +1,9 @@
 extern crate alloc;
 
+use alloc::string::ToString;
 use alloc::vec::Vec;
 use console_error_panic_hook::set_once;
 use wasm_bindgen::prelude::*;
-use crate::consts;
 use crate::world::GameState;
 
 #[wasm_bindgen]
@@ -25,55 +25,80 @@ impl WasmState {
         }
     }
 
-    /// # Panics
+    /// Inputs are length prefixed windows of `Action` codes, one stream per player.
     ///
-    /// Will panic if there is an error in the ticks
+    /// # Errors
+    ///
+    /// Will throw in JS if the inputs are invalid or the match is already over, instead of
+    /// aborting the page
     #[wasm_bindgen]
     #[allow(clippy::needless_pass_by_value)]
-    pub fn tick(&mut self, num_ticks: u32, input: Vec<u32>) {
+    pub fn tick(
+        &mut self,
+        num_ticks: u32,
+        left_input: Vec<u32>,
+        right_input: Vec<u32>,
+    ) -> Result<(), JsError> {
         // TODO: only set_once in debug mode
         set_once();
 
-        match self.inner.tick(num_ticks, &input) {
-            Ok(()) => (),
-            #[allow(clippy::uninlined_format_args)]
-            Err(e) => panic!("Error processing ticks: {}", e),
-        }
+        self.inner
+            .tick(num_ticks, &[&left_input, &right_input])
+            .map_err(|e| JsError::new(&e.to_string()))
     }
 
+    /// # Errors
+    ///
+    /// Will throw in JS if the state fails to encode, instead of aborting the page
     #[wasm_bindgen]
-    #[allow(clippy::must_use_candidate)]
-    pub fn to_cbor(&self) -> Vec<u8> {
-        self.inner.to_serialized_state()
+    pub fn to_cbor(&self) -> Result<Vec<u8>, JsError> {
+        self.inner
+            .to_serialized_state()
+            .map_err(|e| JsError::new(&e.to_string()))
     }
 
+    /// # Errors
+    ///
+    /// Will throw in JS if the ticks fail, see `tick`, or the state fails to encode
     #[wasm_bindgen]
-    pub fn tick_and_return_state(&mut self, ticks: u32, input: Vec<u32>) -> Vec<u8> {
-        self.tick(ticks, input);
+    pub fn tick_and_return_state(
+        &mut self,
+        ticks: u32,
+        left_input: Vec<u32>,
+        right_input: Vec<u32>,
+    ) -> Result<Vec<u8>, JsError> {
+        self.tick(ticks, left_input, right_input)?;
         self.to_cbor()
     }
 
+    /// Match outcome encoded as a u32, see `MatchOutcome::to_u32`
+    #[wasm_bindgen]
+    #[allow(clippy::must_use_candidate)]
+    pub fn outcome(&self) -> u32 {
+        self.inner.outcome().to_u32()
+    }
+
     #[wasm_bindgen]
     #[allow(clippy::must_use_candidate, clippy::missing_const_for_fn)]
     pub fn get_max_x(&self) -> u32 {
-        consts::WORLD_MAX_X
+        self.inner.config.world_max_x
     }
 
     #[wasm_bindgen]
     #[allow(clippy::must_use_candidate, clippy::missing_const_for_fn)]
     pub fn get_max_y(&self) -> u32 {
-        consts::WORLD_MAX_Y
+        self.inner.config.world_max_y
     }
 
     #[wasm_bindgen]
     #[allow(clippy::must_use_candidate, clippy::missing_const_for_fn)]
     pub fn ticks_per_input(&self) -> u32 {
-        consts::TICKS_PER_INPUT
+        self.inner.config.ticks_per_input
     }
 
     #[wasm_bindgen]
     #[allow(clippy::must_use_candidate, clippy::missing_const_for_fn)]
     pub fn tick_input_api_chunk_size(&self) -> u32 {
-        consts::TICK_INPUT_API_CHUNK_SIZE
+        self.inner.config.tick_input_api_chunk_size
     }
 }
@@ -3,14 +3,249 @@ extern crate alloc;
 use alloc::vec::Vec;
 use alloc::string::String;
 use alloc::vec;
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
 use alloy_primitives::B256;
 use alloy_primitives::Bytes;
 use alloy_primitives::keccak256;
+use minicbor::encode::{self, Encoder, Write};
+use minicbor::decode::{self, Decoder};
 use minicbor::{Encode, Decode};
 
-use crate::consts;
+use crate::config::GameConfig;
+use crate::damage::{Armor, Hit};
+use crate::environment::Environment;
+use crate::errors;
+use crate::fixed::Fixed;
+use crate::events::GameEvent;
+use crate::rng::Rng;
+use crate::schema;
+use crate::trig::Angle;
 
-#[derive(Debug, Clone, Copy, Encode, Decode)]
+/// Typed handle to an entity in a `Components` store.
+///
+/// The index picks the slot and the generation counts how often it was reused, so a handle
+/// to a despawned entity never resolves to whatever took its place.
+pub trait GenerationalId: Copy {
+    fn new(index: u32, generation: u32) -> Self;
+
+    fn index(self) -> u32;
+
+    fn generation(self) -> u32;
+}
+
+macro_rules! generational_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
+        #[cbor(map)]
+        pub struct $name {
+            #[n(0)]
+            index: u32,
+            #[n(1)]
+            generation: u32,
+        }
+
+        impl GenerationalId for $name {
+            fn new(index: u32, generation: u32) -> Self {
+                Self { index, generation }
+            }
+
+            fn index(self) -> u32 {
+                self.index
+            }
+
+            fn generation(self) -> u32 {
+                self.generation
+            }
+        }
+    };
+}
+
+generational_id!(
+    /// Rafts are spawned once per player in `PlayerId` order and never despawned
+    RaftId
+);
+generational_id!(FighterId);
+generational_id!(ProjectileId);
+
+#[derive(Clone, Encode, Decode)]
+#[cbor(map)]
+struct Slot<T> {
+    #[n(0)]
+    generation: u32,
+    #[n(1)]
+    component: Option<T>,
+}
+
+/// Component array holding one kind of entity, addressed by generational ids.
+///
+/// Despawned slots are reused lowest index first and iteration follows slot order, so the
+/// same spawns and despawns always leave the store, and its encoding, the same way.
+#[derive(Clone)]
+pub struct Components<I, T> {
+    slots: Vec<Slot<T>>,
+    ids: PhantomData<I>,
+}
+
+// encoded as the bare slots, the id type only matters at compile time
+impl<C, I, T: Encode<C>> Encode<C> for Components<I, T> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>, ctx: &mut C) -> Result<(), encode::Error<W::Error>> {
+        self.slots.encode(e, ctx)
+    }
+}
+
+impl<'b, C, I, T: Decode<'b, C>> Decode<'b, C> for Components<I, T> {
+    fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, decode::Error> {
+        Ok(Self {
+            slots: Vec::decode(d, ctx)?,
+            ids: PhantomData,
+        })
+    }
+}
+
+impl<I: GenerationalId, T> Components<I, T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            slots: vec![],
+            ids: PhantomData,
+        }
+    }
+
+    pub fn spawn(&mut self, component: T) -> I {
+        let free = self.slots.iter().position(|slot| slot.component.is_none());
+        let index = free.unwrap_or_else(|| {
+            self.slots.push(Slot {
+                generation: 0,
+                component: None,
+            });
+            self.slots.len() - 1
+        });
+        let slot = &mut self.slots[index];
+        slot.component = Some(component);
+        I::new(u32::try_from(index).unwrap_or(u32::MAX), slot.generation)
+    }
+
+    /// Removes the entity, its id and any copies of it stop resolving.
+    pub fn despawn(&mut self, id: I) -> Option<T> {
+        let slot = self.slot_mut(id)?;
+        let component = slot.component.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        Some(component)
+    }
+
+    #[must_use]
+    pub fn get(&self, id: I) -> Option<&T> {
+        self.slots
+            .get(id.index() as usize)
+            .filter(|slot| slot.generation == id.generation())
+            .and_then(|slot| slot.component.as_ref())
+    }
+
+    pub fn get_mut(&mut self, id: I) -> Option<&mut T> {
+        self.slot_mut(id).and_then(|slot| slot.component.as_mut())
+    }
+
+    #[must_use]
+    pub fn contains(&self, id: I) -> bool {
+        self.get(id).is_some()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values().count()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values().next().is_none()
+    }
+
+    /// Every live entity with its id, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (I, &T)> {
+        self.slots.iter().zip(0..).filter_map(|(slot, index)| {
+            let component = slot.component.as_ref()?;
+            Some((I::new(index, slot.generation), component))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (I, &mut T)> {
+        self.slots.iter_mut().zip(0..).filter_map(|(slot, index)| {
+            let component = slot.component.as_mut()?;
+            Some((I::new(index, slot.generation), component))
+        })
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = I> + '_ {
+        self.iter().map(|(id, _)| id)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|slot| slot.component.as_ref())
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|slot| slot.component.as_mut())
+    }
+
+    /// Mutable access to two different entities at once, `None` if either is gone or both ids are the same.
+    pub fn get_pair_mut(&mut self, a: I, b: I) -> Option<(&mut T, &mut T)> {
+        let (index_a, index_b) = (a.index() as usize, b.index() as usize);
+        if index_a == index_b || !self.contains(a) || !self.contains(b) {
+            return None;
+        }
+        let (low, high) = self.slots.split_at_mut(index_a.max(index_b));
+        let (first, second) = (low[index_a.min(index_b)].component.as_mut()?, high[0].component.as_mut()?);
+        Some(if index_a < index_b { (first, second) } else { (second, first) })
+    }
+
+    /// Despawns every entity `keep` returns false for.
+    pub fn retain(&mut self, mut keep: impl FnMut(I, &T) -> bool) {
+        let doomed: Vec<I> = self.iter().filter(|(id, c)| !keep(*id, c)).map(|(id, _)| id).collect();
+        for id in doomed {
+            self.despawn(id);
+        }
+    }
+
+    fn slot_mut(&mut self, id: I) -> Option<&mut Slot<T>> {
+        self.slots
+            .get_mut(id.index() as usize)
+            .filter(|slot| slot.generation == id.generation())
+    }
+}
+
+impl<I: GenerationalId, T> Default for Components<I, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: GenerationalId, T> FromIterator<T> for Components<I, T> {
+    fn from_iter<It: IntoIterator<Item = T>>(components: It) -> Self {
+        let mut store = Self::new();
+        for component in components {
+            store.spawn(component);
+        }
+        store
+    }
+}
+
+impl<I: GenerationalId, T> Index<I> for Components<I, T> {
+    type Output = T;
+
+    fn index(&self, id: I) -> &T {
+        self.get(id).expect("stale entity id")
+    }
+}
+
+impl<I: GenerationalId, T> IndexMut<I> for Components<I, T> {
+    fn index_mut(&mut self, id: I) -> &mut T {
+        self.get_mut(id).expect("stale entity id")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
 #[cbor(map)]
 pub enum Bearings {
     #[n(0)]
@@ -31,22 +266,95 @@ pub enum Bearings {
     Southwest,
 }
 
-#[derive(Clone, Encode, Decode)]
+impl Bearings {
+    /// Direction as (dx, dy) where each component is -1, 0 or 1.
+    /// The y axis points up, so North increases y.
+    #[must_use]
+    pub const fn unit(&self) -> (i32, i32) {
+        match self {
+            Self::North => (0, 1),
+            Self::South => (0, -1),
+            Self::East => (1, 0),
+            Self::West => (-1, 0),
+            Self::Northeast => (1, 1),
+            Self::Northwest => (-1, 1),
+            Self::Southeast => (1, -1),
+            Self::Southwest => (-1, -1),
+        }
+    }
+
+    /// Binary angle pointing the same way as `unit`.
+    #[must_use]
+    pub const fn angle(&self) -> Angle {
+        Angle::from_steps(match self {
+            Self::East => 0,
+            Self::Northeast => 128,
+            Self::North => 256,
+            Self::Northwest => 384,
+            Self::West => 512,
+            Self::Southwest => 640,
+            Self::South => 768,
+            Self::Southeast => 896,
+        })
+    }
+
+    #[must_use]
+    pub const fn rotate_clockwise(&self) -> Self {
+        match self {
+            Self::North => Self::Northeast,
+            Self::Northeast => Self::East,
+            Self::East => Self::Southeast,
+            Self::Southeast => Self::South,
+            Self::South => Self::Southwest,
+            Self::Southwest => Self::West,
+            Self::West => Self::Northwest,
+            Self::Northwest => Self::North,
+        }
+    }
+
+    #[must_use]
+    pub const fn rotate_counter_clockwise(&self) -> Self {
+        match self {
+            Self::North => Self::Northwest,
+            Self::Northwest => Self::West,
+            Self::West => Self::Southwest,
+            Self::Southwest => Self::South,
+            Self::South => Self::Southeast,
+            Self::Southeast => Self::East,
+            Self::East => Self::Northeast,
+            Self::Northeast => Self::North,
+        }
+    }
+}
+
+/// Where an entity is in world units, kept to a fraction of a unit.
+#[derive(Debug, Clone, Encode, Decode)]
 #[cbor(map)]
 pub struct Position {
     #[n(0)]
-    pub x: u32,
+    pub x: Fixed,
     #[n(1)]
-    pub y: u32,
+    pub y: Fixed,
 }
 
+/// Horizontal stretch of water a raft has to stay in, bounds apply to the raft's left edge.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cbor(map)]
+pub struct Zone {
+    #[n(0)]
+    pub min_x: u32,
+    #[n(1)]
+    pub max_x: u32,
+}
+
+/// Distance moved per tick in world units, which can be a fraction of a unit.
 #[derive(Clone, Encode, Decode)]
 #[cbor(map)]
 pub struct Velocity {
     #[n(0)]
-    pub vx: i32,
+    pub vx: Fixed,
     #[n(1)]
-    pub vy: i32,
+    pub vy: Fixed,
 }
 
 #[derive(Clone, Encode, Decode)]
@@ -60,7 +368,7 @@ pub struct Entity {
     pub is_active: bool,
 }
 
-#[derive(Clone, Encode, Decode)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
 #[cbor(map)]
 pub enum GunTypes {
     #[n(0)]
@@ -93,10 +401,31 @@ pub struct Raft {
     pub max_health: u32,
     #[n(4)]
     pub curr_health: u32,
+    /// Fighters on board in the order `selected_fighter` cycles through them
     #[n(5)]
-    pub raft_fighters: Vec<RaftFighter>,
+    pub crew: Vec<FighterId>,
     #[n(6)]
     pub style: Style,
+    /// Index into `crew` of the fighter controlled by the player
+    #[n(7)]
+    pub selected_fighter: u32,
+    /// Set by a fire input and consumed by the selected fighter on the same tick
+    #[n(8)]
+    pub fire_requested: bool,
+    /// Rafts sharing a team never damage each other
+    #[n(9)]
+    pub team: u32,
+    /// Heavier rafts gain less speed from each input, see `GameConfig::standard_raft_mass`
+    #[n(10)]
+    pub mass: u32,
+    /// Part of the world the raft may paddle in, see `GameConfig::zone_for_team`
+    #[n(11)]
+    pub zone: Zone,
+    /// Ticks left in the current ram, the raft ignores its zone and hits harder meanwhile
+    #[n(12)]
+    pub ram_ticks: u32,
+    #[n(13)]
+    pub armor: Armor,
 }
 
 #[derive(Clone, Encode, Decode)]
@@ -116,31 +445,60 @@ pub struct RaftFighter {
     pub max_health: u32,
     #[n(6)]
     pub style: Style,
+    #[n(7)]
+    pub aim: Angle,
+    #[n(8)]
+    pub weapon: WeaponState,
+    #[n(9)]
+    pub armor: Armor,
+    /// Raft the fighter stands on
+    #[n(10)]
+    pub raft: RaftId,
 }
 
-impl GunTypes {
+#[derive(Clone, Encode, Decode)]
+#[cbor(map)]
+pub struct WeaponState {
+    /// Ticks left before the gun can fire again
+    #[n(0)]
+    pub cooldown: u32,
+    /// Rounds left before a reload is needed
+    #[n(1)]
+    pub magazine: u32,
+    /// Ticks left until the magazine is refilled, 0 when not reloading
+    #[n(2)]
+    pub reload: u32,
+}
+
+impl WeaponState {
     #[must_use]
-    pub fn fire_rate(&self) -> u32 {
-        match self {
-            GunTypes::SMG => 20,
-            GunTypes::Bazooka => 500,
-            GunTypes::FlameThrower => 100,
-            GunTypes::StraightShooter => 100,
+    pub const fn new(gun: &GunTypes) -> Self {
+        Self {
+            cooldown: 0,
+            magazine: gun.spec().magazine_size,
+            reload: 0,
         }
     }
 }
 
 impl RaftFighter {
-    pub fn new(entity: Entity, gun: GunTypes, width: u32, height: u32) -> Self {
+    /// Fighter that isn't on board yet, `GameState::board` puts it on a raft.
+    #[must_use]
+    pub fn new(entity: Entity, gun: GunTypes, aim: Angle, config: &GameConfig) -> Self {
         let style = gun.style();
+        let weapon = WeaponState::new(&gun);
         Self {
             entity,
-            width,
-            height,
+            width: config.raft_fighter_width,
+            height: config.raft_fighter_height,
             gun,
-            curr_health: consts::DEFAULT_RAFT_HEALTH,
-            max_health: consts::DEFAULT_RAFT_HEALTH,
+            curr_health: config.raft_health,
+            max_health: config.raft_health,
             style,
+            aim,
+            weapon,
+            armor: config.fighter_armor.clone(),
+            raft: RaftId::new(u32::MAX, 0),
         }
     }
 }
@@ -154,18 +512,38 @@ pub struct Projectile {
     pub radius: u32,
     #[n(2)]
     pub style: Style,
+    /// Team of the raft that fired the projectile
+    #[n(3)]
+    pub team: u32,
+    /// Gun that fired the projectile, its `WeaponSpec` drives how the projectile behaves
+    #[n(4)]
+    pub gun: GunTypes,
+    /// Ticks since the projectile was fired
+    #[n(5)]
+    pub age: u32,
+    /// Rafts a piercing projectile already damaged
+    #[n(6)]
+    pub hit_rafts: Vec<RaftId>,
 }
 
 impl Raft {
-    pub fn new(entity: Entity, style: Style) -> Self {
+    #[must_use]
+    pub fn new(entity: Entity, team: u32, style: Style, config: &GameConfig) -> Self {
         Self {
             entity,
-            width: consts::DEFAULT_RAFT_WIDTH,
-            height: consts::DEFAULT_RAFT_HEIGHT,
-            max_health: consts::DEFAULT_RAFT_HEALTH,
-            curr_health: consts::DEFAULT_RAFT_HEALTH,
-            raft_fighters: vec![],
+            width: config.raft_width,
+            height: config.raft_height,
+            max_health: config.raft_health,
+            curr_health: config.raft_health,
+            crew: vec![],
             style,
+            selected_fighter: 0,
+            fire_requested: false,
+            team,
+            mass: config.raft_mass,
+            zone: config.zone_for_team(team),
+            ram_ticks: 0,
+            armor: config.raft_armor.clone(),
         }
     }
 
@@ -182,17 +560,25 @@ impl RaftFighter {
     }
 }
 
-impl GunTypes {
-    pub fn style(&self) -> Style {
-        let color = match self {
-            GunTypes::Bazooka => "#FF0000",
-            GunTypes::SMG => "#00FF00",
-            GunTypes::FlameThrower => "#FFA500",
-            GunTypes::StraightShooter => "#0000FF",
-        };
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOutcome {
+    InProgress,
+    /// Every raft still afloat belongs to `team`
+    Won { team: u32 },
+    Draw,
+}
 
-        Style {
-            color: String::from(color),
+impl MatchOutcome {
+    /// 0 while in progress, 1 or 2 once team 0 or 1 won and 3 for a draw, the codes
+    /// contract events and the frontend read since before there were more than two teams.
+    /// Wins of later teams follow at `team + 2`.
+    #[must_use]
+    pub const fn to_u32(&self) -> u32 {
+        match self {
+            Self::InProgress => 0,
+            Self::Won { team: team @ (0 | 1) } => *team + 1,
+            Self::Draw => 3,
+            Self::Won { team } => team.saturating_add(2),
         }
     }
 }
@@ -200,36 +586,87 @@ impl GunTypes {
 #[derive(Clone, Encode, Decode)]
 #[cbor(map)]
 pub struct GameState {
+    /// Each raft is steered by the input stream of the player it was spawned for, see `PlayerId::raft`
     #[n(0)]
-    pub raft_left: Raft,
+    pub rafts: Components<RaftId, Raft>,
     #[n(1)]
-    pub raft_right: Raft,
+    pub projectiles: Components<ProjectileId, Projectile>,
     #[n(2)]
-    pub left_projectiles: Vec<Projectile>,
+    pub ticks: u32,
     #[n(3)]
-    pub right_projectiles: Vec<Projectile>,
+    pub config: GameConfig,
+    /// Only source of randomness in the simulation
     #[n(4)]
-    pub ticks: u32,
+    pub rng: Rng,
+    #[n(5)]
+    pub environment: Environment,
+    #[n(6)]
+    pub fighters: Components<FighterId, RaftFighter>,
+    /// Events of the most recent tick call, they are output rather than state so they
+    /// are neither serialized nor hashed
+    #[cbor(skip)]
+    pub events: Vec<GameEvent>,
 }
 
 impl Projectile {
-    pub fn new(entity: Entity, radius: u32, gun: GunTypes) -> Self {
+    #[must_use]
+    pub fn new(entity: Entity, radius: u32, gun: GunTypes, team: u32) -> Self {
         let style = gun.style();
         Self {
             entity,
             radius,
             style,
+            team,
+            gun,
+            age: 0,
+            hit_rafts: vec![],
+        }
+    }
+
+    /// Damage of a direct hit, falling off linearly over the gun's range if it has one.
+    #[must_use]
+    pub const fn damage(&self) -> u32 {
+        let spec = self.gun.spec();
+        if spec.range == 0 {
+            return spec.damage;
+        }
+        spec.damage * spec.range.saturating_sub(self.age) / spec.range
+    }
+
+    /// Direct hit dealt to whatever the projectile runs into.
+    #[must_use]
+    pub const fn hit(&self) -> Hit {
+        let spec = self.gun.spec();
+        Hit {
+            damage: self.damage(),
+            damage_type: spec.damage_type,
+            crit_chance: spec.crit_chance,
+            crit_multiplier: spec.crit_multiplier,
         }
     }
 }
 
 impl GameState {
-    pub fn to_serialized_state(&self) -> Vec<u8> {
-        minicbor::to_vec(self).expect("CBOR encoding failed")
+    /// Encoding tagged with the current `schema::STATE_VERSION`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if some part of the state fails to encode
+    pub fn to_serialized_state(&self) -> errors::Result<Vec<u8>> {
+        let mut encoded = Vec::new();
+        schema::encode_state(self, &mut encoded)?;
+        Ok(encoded)
     }
 
-    pub fn from_serialized_state(encoded: &Vec<u8>) -> Self {
-        minicbor::decode(encoded).expect("CBOR decoding failed")
+    /// Decodes a state of the current or an older version, see `schema::decode_state`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` with the byte position minicbor stopped at if `encoded` isn't a
+    /// valid game state, if its version is newer than this build supports or if its config
+    /// fails `GameConfig::validate`
+    pub fn from_serialized_state(encoded: &[u8]) -> errors::Result<Self> {
+        schema::decode_state(encoded)
     }
     // pub fn to_serialized_state(&self) -> Bytes {
     //     Bytes::from(minicbor::to_vec(self).expect("CBOR encoding failed"))
@@ -239,9 +676,12 @@ impl GameState {
     //     minicbor::decode(encoded).expect("CBOR decoding failed")
     // }
 
-    pub fn hash(&self) -> B256 {
-        let serialized_game_state = self.to_serialized_state();
+    /// # Errors
+    ///
+    /// Will return `Err` if the state fails to encode
+    pub fn hash(&self) -> errors::Result<B256> {
+        let serialized_game_state = self.to_serialized_state()?;
         let hash = keccak256(&serialized_game_state);
-        hash
+        Ok(hash)
     }
 }
@@ -1,37 +1,33 @@
 #[derive(Debug, PartialEq, Eq)]
-pub enum GameInput {
+pub enum Action {
     NoOp,
-    DeprecatedShootLeftRaft,
-    MoveLeftRaftRight,
-    MoveLeftRaftLeft,
-    DeprecatedShootRightRaft,
-    MoveRightRaftRight,
-    MoveRightRaftLeft,
-    DeprecatedAddProjectile,
-    MoveLeftFastRaftLeft,
-    MoveLeftFastRaftRight,
-    MoveUpRaftRight,
-    MoveRightRaftDown,
-    MoveUpRaftLeft,
-    MoveLeftRaftDown,
+    Fire,
+    MoveRight,
+    MoveLeft,
+    MoveUp,
+    MoveDown,
+    Charge,
+    SelectNextFighter,
+    AimClockwise,
+    AimCounterClockwise,
+    Ram,
+    AimAtEnemy,
 }
 
-impl GameInput {
+impl Action {
     pub const fn to_u32(&self) -> u32 {
         match self {
-            Self::DeprecatedShootLeftRaft => 0,
-            Self::MoveLeftRaftRight => 1,
-            Self::MoveLeftRaftLeft => 2,
-            Self::DeprecatedShootRightRaft => 3,
-            Self::MoveRightRaftRight => 4,
-            Self::MoveRightRaftLeft => 5,
-            Self::DeprecatedAddProjectile => 6,
-            Self::MoveLeftFastRaftLeft => 7,
-            Self::MoveLeftFastRaftRight => 8,
-            Self::MoveUpRaftRight => 9,
-            Self::MoveRightRaftDown => 10,
-            Self::MoveUpRaftLeft => 11,
-            Self::MoveLeftRaftDown => 12,
+            Self::Fire => 0,
+            Self::MoveRight => 1,
+            Self::MoveLeft => 2,
+            Self::MoveUp => 3,
+            Self::MoveDown => 4,
+            Self::Charge => 5,
+            Self::SelectNextFighter => 6,
+            Self::AimClockwise => 7,
+            Self::AimCounterClockwise => 8,
+            Self::Ram => 9,
+            Self::AimAtEnemy => 10,
             Self::NoOp => 86,
         }
     }
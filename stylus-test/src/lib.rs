@@ -4,4 +4,4 @@ pub mod game;
 pub mod paddle;
 pub mod tests;
 
-pub use game::{GameInput, TICKS_PER_INPUT, TICK_INPUT_API_CHUNK_SIZE};
+pub use game::{Action, TICKS_PER_INPUT, TICK_INPUT_API_CHUNK_SIZE};
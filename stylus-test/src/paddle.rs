@@ -1,12 +1,12 @@
-use crate::game::GameInput;
+use crate::game::Action;
 use crate::game::TICKS_PER_INPUT;
 use crate::game::TICK_INPUT_API_CHUNK_SIZE;
 use paddle_battle::world::GameState;
 use paddle_battle::errors::Result;
 
-pub fn simulate_game_state(ticks: u32, inputs: &[u32]) -> Result<GameState> {
+pub fn simulate_game_state(ticks: u32, left_inputs: &[u32], right_inputs: &[u32]) -> Result<GameState> {
     let mut game_state = GameState::new();
-    game_state.tick(ticks, &inputs.to_vec())?;
+    game_state.tick(ticks, &[left_inputs, right_inputs])?;
     Ok(game_state)
 }
 
@@ -16,36 +16,42 @@ mod tests {
 
     #[test]
     fn test_local_simulation() {
-        let mut input_codes: Vec<u32> = Vec::new();
+        let mut left_codes: Vec<u32> = Vec::new();
+        let mut right_codes: Vec<u32> = Vec::new();
 
-        // Add input codes based on simulated key presses
-        input_codes.push(GameInput::MoveRightRaftLeft.to_u32());
-        input_codes.push(GameInput::MoveLeftRaftRight.to_u32());
-        input_codes.push(GameInput::MoveRightRaftLeft.to_u32());
-        input_codes.push(GameInput::MoveLeftRaftRight.to_u32());
-        input_codes.push(GameInput::MoveRightRaftLeft.to_u32());
-        input_codes.push(GameInput::MoveLeftRaftRight.to_u32());
-        input_codes.push(GameInput::MoveUpRaftLeft.to_u32());
+        // Add input codes based on simulated key presses, each player only steers their own raft
+        right_codes.push(Action::MoveLeft.to_u32());
+        left_codes.push(Action::MoveRight.to_u32());
+        right_codes.push(Action::MoveLeft.to_u32());
+        left_codes.push(Action::MoveRight.to_u32());
+        right_codes.push(Action::MoveLeft.to_u32());
+        left_codes.push(Action::MoveRight.to_u32());
+        left_codes.push(Action::MoveUp.to_u32());
+        left_codes.push(Action::Fire.to_u32());
+        right_codes.push(Action::Fire.to_u32());
 
-        // Pad the input array to match TICK_INPUT_API_CHUNK_SIZE
-        while input_codes.len() < TICK_INPUT_API_CHUNK_SIZE as usize {
-            input_codes.push(GameInput::NoOp.to_u32());
-        }
+        // Each input window is sent with a length prefix, no NoOp padding needed
+        assert!(left_codes.len() <= TICK_INPUT_API_CHUNK_SIZE as usize);
+        assert!(right_codes.len() <= TICK_INPUT_API_CHUNK_SIZE as usize);
 
         // Create array of input arrays for multiple ticks
         let num_ticks = 1000;
         let inputs_needed = num_ticks / TICKS_PER_INPUT;
-        let mut final_inputs: Vec<u32> = Vec::new();
+        let mut left_inputs: Vec<u32> = Vec::new();
+        let mut right_inputs: Vec<u32> = Vec::new();
         for _ in 0..inputs_needed {
-            final_inputs.extend(&input_codes);
+            left_inputs.push(left_codes.len() as u32);
+            left_inputs.extend(&left_codes);
+            right_inputs.push(right_codes.len() as u32);
+            right_inputs.extend(&right_codes);
         }
 
-        let game_state = simulate_game_state(num_ticks, &final_inputs).unwrap();
+        let game_state = simulate_game_state(num_ticks, &left_inputs, &right_inputs).unwrap();
         
         // Verify the game state matches the on-chain state
-        assert_eq!(game_state.raft_left.curr_health, 10_000);
-        assert_eq!(game_state.raft_right.curr_health, 9_500);
-        assert_eq!(game_state.left_projectiles.len(), 45);
-        assert_eq!(game_state.right_projectiles.len(), 52);
+        assert_eq!(game_state.team_health(0), 9_802);
+        assert_eq!(game_state.team_health(1), 9_972);
+        assert_eq!(game_state.projectile_count(0), 0);
+        assert_eq!(game_state.projectile_count(1), 0);
     }
 } 
\ No newline at end of file
@@ -35,6 +35,17 @@ const buttonPressed = {
   "Z": false,
   " ": false,
   "w": false,
+  "f": false,
+  "r": false,
+  "q": false,
+  "e": false,
+  "Enter": false,
+  "/": false,
+  ",": false,
+  ".": false,
+  "x": false,
+  "X": false,
+  "m": false,
 };
 
 type TKeyButtonPressed = keyof typeof buttonPressed
@@ -83,27 +94,18 @@ const PaddleGame: React.FC = () => {
 
     ctx.clearRect(0, 0, canvasWidth, canvasHeight);
 
-    const raftLeft = gameState.raft_left;
-    console.log('Raft Left Color:', raftLeft.style.color);
-    ctx.fillStyle = raftLeft.style.color;
-    ctx.fillRect(
-      raftLeft.entity.position.x * scaleX,
-      raftLeft.entity.position.y * scaleY,
-      raftLeft.width * scaleX,
-      raftLeft.height * scaleY
-    );
-
-    const raftRight = gameState.raft_right;
-    console.log('Raft Right Color:', raftRight.style.color);
-    ctx.fillStyle = raftRight.style.color;
-    ctx.fillRect(
-      raftRight.entity.position.x * scaleX,
-      raftRight.entity.position.y * scaleY,
-      raftRight.width * scaleX,
-      raftRight.height * scaleY
-    );
+    gameState.rafts.forEach((raft, index) => {
+      console.log(`Raft ${index} Color:`, raft.style.color);
+      ctx.fillStyle = raft.style.color;
+      ctx.fillRect(
+        raft.entity.position.x * scaleX,
+        raft.entity.position.y * scaleY,
+        raft.width * scaleX,
+        raft.height * scaleY
+      );
+    });
 
-    [...gameState.left_projectiles, ...gameState.right_projectiles].forEach((projectile) => {
+    gameState.projectiles.forEach((projectile) => {
       const { radius, style } = projectile;
       console.log('Projectile Color:', style.color);
       ctx.fillStyle = style.color;
@@ -118,23 +120,8 @@ const PaddleGame: React.FC = () => {
       ctx.fill();
     });
 
-    gameState.raft_left.raft_fighters.forEach((fighter) => {
-      console.log('Raft Left Fighter Color:', fighter.style.color);
-      ctx.fillStyle = fighter.style.color;
-      ctx.fillRect(
-        fighter.entity.position.x * scaleX,
-        fighter.entity.position.y * scaleY,
-        fighter.width * scaleX,
-        fighter.height * scaleY
-      );
-      ctx.fillStyle = '#000000';
-      ctx.fillText(`HP: ${fighter.curr_health}`,
-        fighter.entity.position.x * scaleX + 50,
-        (fighter.entity.position.y - 10) * scaleY
-      );
-    });
-    gameState.raft_right.raft_fighters.forEach((fighter) => {
-      console.log('Raft Right Fighter Color:', fighter.style.color);
+    gameState.fighters.forEach((fighter) => {
+      console.log(`Raft ${fighter.raft.index} Fighter Color:`, fighter.style.color);
       ctx.fillStyle = fighter.style.color;
       ctx.fillRect(
         fighter.entity.position.x * scaleX,
@@ -168,27 +155,37 @@ const PaddleGame: React.FC = () => {
       ctx.setTransform(1, 0, 0, -1, 0, canvasRef.current.height);
       console.log('Canvas transformation set:', ctx.getTransform());
 
-      const inputCodes: number[] = [];
+      // each player has their own input stream and can only steer their own raft
+      const leftCodes: number[] = [];
+      const rightCodes: number[] = [];
 
       // TODO: iterate over keys of buttonPressed with TKeyButtonPressed and leverage a switch case with typescript ensuring the check is exhaustive
       // this will ensure keypresses are maintained correctly
-      if (buttonPressed["s"] || buttonPressed["S"]) inputCodes.push(0);
-      if (buttonPressed["d"] || buttonPressed["D"]) inputCodes.push(1);
-      if (buttonPressed["a"] || buttonPressed["A"]) inputCodes.push(2);
-      if (buttonPressed["ArrowDown"]) inputCodes.push(3);
-      if (buttonPressed["ArrowRight"]) inputCodes.push(4);
-      if (buttonPressed["ArrowLeft"]) inputCodes.push(5);
-      if (buttonPressed["p"] || buttonPressed["P"]) inputCodes.push(6);
-      if (buttonPressed["z"] || buttonPressed["Z"]) inputCodes.push(7);
-      if (buttonPressed[" "]) inputCodes.push(8);
-      if (buttonPressed["ArrowUp"]) inputCodes.push(9);
-      if (buttonPressed["ArrowDown"]) inputCodes.push(10);
-      if (buttonPressed["w"]) inputCodes.push(11);
-      if (buttonPressed["s"]) inputCodes.push(12);
-      if (buttonPressed["Escape"]) inputCodes.push(86);
-
-      while (inputCodes.length < TICK_INPUT_API_CHUNK_SIZE) {
-        inputCodes.push(86);
+      if (buttonPressed["f"]) leftCodes.push(0);
+      if (buttonPressed["d"] || buttonPressed["D"]) leftCodes.push(1);
+      if (buttonPressed["a"] || buttonPressed["A"]) leftCodes.push(2);
+      if (buttonPressed["w"]) leftCodes.push(3);
+      if (buttonPressed["s"]) leftCodes.push(4);
+      if (buttonPressed["z"] || buttonPressed["Z"]) leftCodes.push(5);
+      if (buttonPressed["r"]) leftCodes.push(6);
+      if (buttonPressed["e"]) leftCodes.push(7);
+      if (buttonPressed["q"]) leftCodes.push(8);
+      if (buttonPressed["x"] || buttonPressed["X"]) leftCodes.push(9);
+
+      if (buttonPressed["Enter"]) rightCodes.push(0);
+      if (buttonPressed["ArrowRight"]) rightCodes.push(1);
+      if (buttonPressed["ArrowLeft"]) rightCodes.push(2);
+      if (buttonPressed["ArrowUp"]) rightCodes.push(3);
+      if (buttonPressed["ArrowDown"]) rightCodes.push(4);
+      if (buttonPressed[" "]) rightCodes.push(5);
+      if (buttonPressed["/"]) rightCodes.push(6);
+      if (buttonPressed["."]) rightCodes.push(7);
+      if (buttonPressed[","]) rightCodes.push(8);
+      if (buttonPressed["m"]) rightCodes.push(9);
+
+      if (buttonPressed["Escape"]) {
+        leftCodes.push(86);
+        rightCodes.push(86);
       }
 
       let initial_tick = tickCounterRef.current;
@@ -196,14 +193,19 @@ const PaddleGame: React.FC = () => {
       let inputs_needed = TICKS_PER_LOOP / TICKS_PER_INPUT +
         TICKS_PER_LOOP % TICKS_PER_INPUT > 0 ? 1 : 0;
 
-      let final: Array<typeof inputCodes> = [];
-      for (let i = 0; i < inputs_needed; i++) {
-        final.push(inputCodes)
-      }
+      // every input window is prefixed with its length, no NoOp padding needed
+      const toStream = (codes: number[]) => {
+        const windowCodes = codes.slice(0, TICK_INPUT_API_CHUNK_SIZE);
+        const inputWindow = [windowCodes.length, ...windowCodes];
+
+        let final: Array<typeof inputWindow> = [];
+        for (let i = 0; i < inputs_needed; i++) {
+          final.push(inputWindow)
+        }
+        return new Uint32Array(final.flat());
+      };
 
-      const array = new Uint32Array(final.flat());
-      
-      const stateBytes = wasmRef.current.tick_and_return_state(TICKS_PER_LOOP, array);
+      const stateBytes = wasmRef.current.tick_and_return_state(TICKS_PER_LOOP, toStream(leftCodes), toStream(rightCodes));
       const state = parseGameState(new Uint8Array(stateBytes));
       setGameState(state);
       drawGame(ctx, state);
@@ -291,16 +293,16 @@ const PaddleGame: React.FC = () => {
             <br />
             Paddle Left
             <br />
-            Max health: {gameState?.raft_left.max_health}
+            Max health: {gameState?.rafts[0].max_health}
             <br />
-            Curr health: {gameState?.raft_left.curr_health}
+            Curr health: {gameState?.rafts[0].curr_health}
             <br />
             <br />
             Paddle Right
             <br />
-            Max health: {gameState?.raft_right.max_health}
+            Max health: {gameState?.rafts[1].max_health}
             <br />
-            Curr health: {gameState?.raft_right.curr_health}
+            Curr health: {gameState?.rafts[1].curr_health}
           </p>
         </div>
       )}
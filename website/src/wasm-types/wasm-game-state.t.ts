@@ -1,13 +1,16 @@
 import * as cbor from 'cbor-web';
 
+// Positions and velocities are fixed point in Rust, encoded as raw bits with 16 fractional bits
+const FIXED_ONE = 1 << 16;
+
 type Position = {
-    x: number;  // u32 in Rust
-    y: number;  // u32 in Rust
+    x: number;  // Fixed in Rust, converted to world units
+    y: number;  // Fixed in Rust, converted to world units
 };
 
 type Velocity = {
-    vx: number;  // i32 in Rust
-    vy: number;  // i32 in Rust
+    vx: number;  // Fixed in Rust, converted to world units per tick
+    vy: number;  // Fixed in Rust, converted to world units per tick
 };
 
 type Entity = {
@@ -22,20 +25,57 @@ type Style = {
 
 type GunTypes = "Bazooka" | "SMG" | "FlameThrower" | "StraightShooter";
 
+// Generational handle into one of the GameState component arrays
+type EntityId = {
+    index: number;  // u32 in Rust
+    generation: number;  // u32 in Rust
+};
+
+// Live entity of a component array along with the id it is stored under
+type Spawned<T> = T & { id: EntityId };
+
 type Raft = {
     entity: Entity;
-    raft_fighters: RaftFighter[];
+    crew: EntityId[];  // ids into GameState.fighters
     width: number;  // u32 in Rust
     height: number;  // u32 in Rust
     max_health: number;  // u32 in Rust
     curr_health: number;  // u32 in Rust
     style: Style;
+    selected_fighter: number;  // u32 in Rust
+    fire_requested: boolean;
+    team: number;  // u32 in Rust
+    mass: number;  // u32 in Rust
+    zone: Zone;
+    ram_ticks: number;  // u32 in Rust
+    armor: Armor;
+};
+
+type Resistances = {
+    kinetic: number;  // i32 in Rust, percent
+    explosive: number;
+    fire: number;
+    impact: number;
+};
+
+type Armor = {
+    rating: number;  // u32 in Rust
+    resistances: Resistances;
+};
+
+type Zone = {
+    min_x: number;  // u32 in Rust
+    max_x: number;  // u32 in Rust
 };
 
 type Projectile = {
     entity: Entity;
     radius: number;  // u32 in Rust
     style: Style;
+    team: number;  // u32 in Rust
+    gun: GunTypes;
+    age: number;  // u32 in Rust
+    hit_rafts: EntityId[];
 };
 
 type RaftFighter = {
@@ -46,13 +86,22 @@ type RaftFighter = {
     max_health: number;  // u32 in Rust
     curr_health: number;  // u32 in Rust
     style: Style;
+    aim: number;  // trig::Angle in Rust, steps counter-clockwise from east, 1024 per turn
+    weapon: WeaponState;
+    armor: Armor;
+    raft: EntityId;
 }
 
+type WeaponState = {
+    cooldown: number;  // u32 in Rust
+    magazine: number;  // u32 in Rust
+    reload: number;  // u32 in Rust
+};
+
 export type GameState = {
-    raft_left: Raft;
-    raft_right: Raft;
-    left_projectiles: Projectile[];
-    right_projectiles: Projectile[];
+    rafts: Spawned<Raft>[];
+    projectiles: Spawned<Projectile>[];
+    fighters: Spawned<RaftFighter>[];
     ticks: number;  // u32 in Rust
 };
 
@@ -75,8 +124,7 @@ const isGunTypes = (obj: any): obj is GunTypes => {
 const isRaft = (obj: any): obj is Raft => {
     return obj &&
         isEntity(obj.entity) &&
-        Array.isArray(obj.raft_fighters) &&
-        obj.raft_fighters.every(isRaftFighter) &&
+        Array.isArray(obj.crew) &&
         typeof obj.width === 'number' &&
         typeof obj.height === 'number' &&
         typeof obj.curr_health === 'number' &&
@@ -105,15 +153,14 @@ const isStyle = (obj: any): obj is Style => {
 
 const isGameState = (obj: any): obj is GameState => {
     return obj &&
-        isRaft(obj.raft_left) &&
-        isRaft(obj.raft_right) &&
-        Array.isArray(obj.left_projectiles) && obj.left_projectiles.every(isProjectile) &&
-        Array.isArray(obj.right_projectiles) && obj.right_projectiles.every(isProjectile) &&
+        Array.isArray(obj.rafts) && obj.rafts.every(isRaft) &&
+        Array.isArray(obj.projectiles) && obj.projectiles.every(isProjectile) &&
+        Array.isArray(obj.fighters) && obj.fighters.every(isRaftFighter) &&
         typeof obj.ticks === 'number';
 }
 
 // Type mappings configuration
-type TypeName = 'Raft' | 'Entity' | 'Style' | 'Projectile' | 'RaftFighter' | 'Position' | 'Velocity' | 'GunTypes';
+type TypeName = 'EntityId' | 'Raft' | 'Entity' | 'Style' | 'Projectile' | 'RaftFighter' | 'Position' | 'Velocity' | 'GunTypes' | 'WeaponState' | 'Zone' | 'Armor' | 'Resistances';
 
 type TypeConfig = {
     fields?: readonly string[];
@@ -124,12 +171,16 @@ type TypeConfig = {
 };
 
 const TYPE_MAPPINGS: Record<TypeName, TypeConfig> = {
+    EntityId: {
+        fields: ['index', 'generation']
+    },
     Raft: {
-        fields: ['entity', 'width', 'height', 'max_health', 'curr_health', 'raft_fighters', 'style'],
+        fields: ['entity', 'width', 'height', 'max_health', 'curr_health', 'crew', 'style', 'selected_fighter', 'fire_requested', 'team', 'mass', 'zone', 'ram_ticks', 'armor'],
         nestedTypes: {
             entity: 'Entity',
             style: 'Style',
-            raft_fighters: 'RaftFighter'
+            zone: 'Zone',
+            armor: 'Armor'
         }
     },
     Entity: {
@@ -143,26 +194,45 @@ const TYPE_MAPPINGS: Record<TypeName, TypeConfig> = {
         fields: ['color']
     },
     Projectile: {
-        fields: ['entity', 'radius', 'style'],
+        fields: ['entity', 'radius', 'style', 'team', 'gun', 'age', 'hit_rafts'],
         nestedTypes: {
             entity: 'Entity',
-            style: 'Style'
+            style: 'Style',
+            gun: 'GunTypes'
         }
     },
     RaftFighter: {
-        fields: ['entity', 'width', 'height', 'gun', 'curr_health', 'max_health', 'style'],
+        fields: ['entity', 'width', 'height', 'gun', 'curr_health', 'max_health', 'style', 'aim', 'weapon', 'armor', 'raft'],
         nestedTypes: {
             entity: 'Entity',
             style: 'Style',
-            gun: 'GunTypes'
+            gun: 'GunTypes',
+            weapon: 'WeaponState',
+            armor: 'Armor',
+            raft: 'EntityId'
         }
     },
+    WeaponState: {
+        fields: ['cooldown', 'magazine', 'reload']
+    },
     Position: {
         fields: ['x', 'y']
     },
     Velocity: {
         fields: ['vx', 'vy']
     },
+    Zone: {
+        fields: ['min_x', 'max_x']
+    },
+    Armor: {
+        fields: ['rating', 'resistances'],
+        nestedTypes: {
+            resistances: 'Resistances'
+        }
+    },
+    Resistances: {
+        fields: ['kinetic', 'explosive', 'fire', 'impact']
+    },
     GunTypes: {
         values: ['Bazooka', 'SMG', 'FlameThrower', 'StraightShooter']
     }
@@ -189,16 +259,8 @@ const mapToObject = (map: Map<number, any>, type: TypeName | null = null): any =
             const nestedType = typeConfig?.nestedTypes?.[fieldName] || null;
             obj[fieldName] = mapToObject(value, nestedType);
         } else if (Array.isArray(value)) {
-            if (fieldName === 'raft_fighters') {
-                // Handle raft_fighters array - each item is a Map that needs to be parsed as RaftFighter
-                obj[fieldName] = value.map(item => {
-                    if (item instanceof Map) {
-                        return mapToObject(item, 'RaftFighter');
-                    }
-                    return item;
-                });
-            } else if (fieldName === 'left_projectiles' || fieldName === 'right_projectiles') {
-                obj[fieldName] = value.map(item => mapToObject(item, 'Projectile'));
+            if (fieldName === 'crew' || fieldName === 'hit_rafts') {
+                obj[fieldName] = value.map(item => mapToObject(item, 'EntityId'));
             } else if (fieldName === 'gun') {
                 // Handle gun array case - take the first value as the enum variant
                 const gunValues = TYPE_MAPPINGS.GunTypes.values;
@@ -208,6 +270,8 @@ const mapToObject = (map: Map<number, any>, type: TypeName | null = null): any =
             } else {
                 obj[fieldName] = value.map(item => mapToObject(item));
             }
+        } else if (type === 'Position' || type === 'Velocity') {
+            obj[fieldName] = Number(value) / FIXED_ONE;
         } else {
             obj[fieldName] = value;
         }
@@ -215,17 +279,37 @@ const mapToObject = (map: Map<number, any>, type: TypeName | null = null): any =
     return obj;
 };
 
+// Component arrays are encoded as their slots, each a map of {0: generation, 1: value}
+// where despawned slots hold null. Only live entities are kept, tagged with their id.
+const parseComponents = (slots: Map<number, any>[], type: TypeName): any[] => {
+    return slots.flatMap((slot, index) => {
+        const value = slot.get(1);
+        if (value == null) return [];
+        return [{ ...mapToObject(value, type), id: { index, generation: slot.get(0) } }];
+    });
+};
+
+// Latest layout version this parser understands, see `schema::STATE_VERSION` in Rust
+const STATE_VERSION = 3;
+
 export const parseGameState = (data: Uint8Array): GameState => {
     try {
-        const decoded = cbor.decode(data);
+        // states are wrapped in a [version, state] envelope, a bare map predates it
+        const envelope = cbor.decode(data);
+        if (!Array.isArray(envelope)) {
+            throw new Error("unversioned game state");
+        }
+        const [version, decoded] = envelope;
+        if (version > STATE_VERSION) {
+            throw new Error(`unsupported game state version ${version}`);
+        }
         
         // Convert Map to plain object
         const parsedData = {
-            raft_left: mapToObject(decoded.get(0), 'Raft'),
-            raft_right: mapToObject(decoded.get(1), 'Raft'),
-            left_projectiles: decoded.get(2).map((item: Map<number, any>) => mapToObject(item, 'Projectile')),
-            right_projectiles: decoded.get(3).map((item: Map<number, any>) => mapToObject(item, 'Projectile')),
-            ticks: decoded.get(4)
+            rafts: parseComponents(decoded.get(0), 'Raft'),
+            projectiles: parseComponents(decoded.get(1), 'Projectile'),
+            fighters: parseComponents(decoded.get(6), 'RaftFighter'),
+            ticks: decoded.get(2)
         };
         
         if (isGameState(parsedData)) return parsedData;